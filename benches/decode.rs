@@ -0,0 +1,57 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rfbutton::{decode, decode_all, Code};
+
+/// A real capture of a doorbell button, with several repeated frames.
+const DOORBELL: [u16; 299] = [
+    320, 10060, 320, 960, 960, 300, 300, 960, 320, 960, 960, 300, 300, 960, 300, 980, 300, 960,
+    960, 300, 320, 960, 960, 300, 960, 320, 300, 960, 300, 960, 960, 320, 300, 960, 960, 320, 300,
+    960, 960, 320, 300, 960, 300, 960, 980, 300, 300, 960, 320, 960, 300, 10080, 320, 960, 960,
+    320, 300, 960, 300, 960, 980, 300, 300, 960, 320, 960, 300, 960, 960, 320, 300, 960, 960, 320,
+    960, 300, 300, 960, 320, 960, 960, 300, 320, 960, 960, 300, 320, 960, 960, 300, 320, 960, 300,
+    960, 960, 320, 300, 960, 320, 960, 300, 10080, 320, 960, 960, 320, 300, 960, 300, 960, 960,
+    320, 300, 960, 320, 960, 300, 960, 960, 320, 300, 960, 960, 320, 960, 300, 320, 960, 300, 960,
+    960, 320, 300, 960, 960, 320, 300, 960, 960, 320, 300, 960, 300, 960, 980, 300, 300, 960, 320,
+    960, 300, 10100, 300, 980, 960, 300, 300, 960, 320, 960, 960, 300, 320, 960, 300, 960, 300,
+    980, 960, 300, 320, 960, 960, 300, 960, 320, 300, 960, 320, 960, 960, 300, 320, 960, 960, 300,
+    320, 960, 960, 300, 320, 960, 300, 960, 960, 320, 300, 960, 300, 960, 320, 10100, 300, 960,
+    960, 320, 300, 960, 320, 940, 980, 300, 300, 980, 300, 960, 300, 960, 980, 300, 300, 960, 960,
+    320, 960, 320, 300, 960, 300, 960, 980, 300, 300, 960, 960, 320, 300, 960, 980, 300, 300, 960,
+    320, 960, 960, 300, 320, 960, 300, 960, 320, 10080, 320, 960, 960, 300, 320, 960, 300, 960,
+    960, 320, 300, 960, 320, 960, 300, 960, 960, 320, 300, 960, 960, 320, 960, 300, 320, 960, 300,
+    960, 960, 320, 300, 960, 960, 320, 300, 960, 960, 320, 300, 960, 320, 960, 960, 300, 320, 960,
+    300,
+];
+
+fn bench_decode(c: &mut Criterion) {
+    let long_capture = Code {
+        value: 0x48b2a4,
+        length: 24,
+    }
+    .repeat_pulses(320, 100, 9920);
+    // About 50,000 pulses, as from a capture several seconds long.
+    let huge_capture = Code {
+        value: 0x48b2a4,
+        length: 24,
+    }
+    .repeat_pulses(320, 1000, 9920);
+
+    c.bench_function("decode doorbell", |b| {
+        b.iter(|| decode(black_box(&DOORBELL)))
+    });
+    c.bench_function("decode_all doorbell", |b| {
+        b.iter(|| decode_all(black_box(&DOORBELL)))
+    });
+    c.bench_function("decode_all 100 frames", |b| {
+        b.iter(|| decode_all(black_box(&long_capture)))
+    });
+    c.bench_function("decode_all 50k pulses", |b| {
+        b.iter(|| decode_all(black_box(&huge_capture)))
+    });
+}
+
+criterion_group!(benches, bench_decode);
+criterion_main!(benches);
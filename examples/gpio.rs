@@ -9,11 +9,11 @@ use cc1101::{
     TargetAmplitude,
 };
 use embedded_hal_bus::spi::ExclusiveDevice;
-use eyre::{bail, eyre, Context, Report};
+use eyre::{eyre, Report};
 use log::{debug, trace};
-use rfbutton::decode;
+use rfbutton::{decode_with, DecodeOptions, EdgeCapture};
 use rppal::{
-    gpio::{Event, Gpio, InputPin, Level, Trigger},
+    gpio::{Gpio, InputPin, Trigger},
     hal::Delay,
     spi::{Bus, Mode, SlaveSelect, Spi},
 };
@@ -88,6 +88,12 @@ fn main() -> Result<(), Report> {
 
     rx_pin.set_interrupt(Trigger::Both, None)?;
 
+    // Use the same break pulse length for decoding as for capturing.
+    let options = DecodeOptions {
+        break_pulse_length: BREAK_PULSE_LENGTH.as_micros().try_into()?,
+        ..Default::default()
+    };
+
     loop {
         match receive(&mut rx_pin) {
             Ok(pulses) => {
@@ -96,7 +102,7 @@ fn main() -> Result<(), Report> {
                 } else {
                     println!("{} pulses: {:?}", pulses.len(), pulses);
                 }
-                match decode(&pulses) {
+                match decode_with(&pulses, &options) {
                     Ok(code) => {
                         if code.length > 0 {
                             println!("Decoded: {:?}", code);
@@ -121,90 +127,24 @@ fn main() -> Result<(), Report> {
 
 /// Wait for a single code.
 fn receive(rx_pin: &mut InputPin) -> Result<Vec<u16>, Report> {
-    debug!("Waiting for interrupt...");
-    let level = rx_pin.poll_interrupt(false, None)?;
-    if level.is_none() {
-        bail!("Unexpected initial level {:?}", level);
-    }
-    debug!("Initial level: {:?}", level);
-    let mut last_timestamp = Instant::now();
-    let mut pulses = Vec::new();
+    let mut capture = EdgeCapture::with_break_pulse_length(Instant::now(), BREAK_PULSE_LENGTH);
 
     debug!("Waiting for initial break pulse...");
-    // Wait for a long pulse to start.
-    let mut last_pulse = Duration::default();
-    while let Some(event) = rx_pin.poll_interrupt(false, None)? {
-        let timestamp = Instant::now();
-        let pulse_length = timestamp - last_timestamp;
-        last_timestamp = timestamp;
-
-        if event.trigger == Trigger::RisingEdge && pulse_length > BREAK_PULSE_LENGTH {
-            trace!(
-                "Found possible initial break pulse {} μs.",
-                pulse_length.as_micros()
-            );
-        } else if event.trigger == Trigger::FallingEdge
-            && last_pulse > BREAK_PULSE_LENGTH
-            && pulse_length < BREAK_PULSE_LENGTH
-        {
-            debug!(
-                "Found initial break pulse {} μs and first pulse {} μs.",
-                last_pulse.as_micros(),
-                pulse_length.as_micros()
-            );
-            pulses.push(
-                last_pulse
-                    .as_micros()
-                    .try_into()
-                    .context("Pulse length too long")?,
-            );
-            pulses.push(
-                pulse_length
-                    .as_micros()
-                    .try_into()
-                    .context("Pulse length too long")?,
-            );
-            break;
+    loop {
+        // Wait for as long as it takes for the start of a code, but then only for the maximum pulse
+        // length.
+        let timeout = capture.is_capturing().then_some(MAX_PULSE_LENGTH);
+        if let Some(event) = rx_pin.poll_interrupt(false, timeout)? {
+            trace!("{:?}", event.trigger);
+            if let Some(pulses) = capture.edge(event.trigger == Trigger::RisingEdge) {
+                debug!("Found final break pulse.");
+                return Ok(pulses);
+            }
         } else {
-            trace!(
-                "Ignoring {} μs {:?} pulse.",
-                pulse_length.as_micros(),
-                previous_level(&event),
-            );
+            debug!("Timed out waiting for next pulse.");
+            return capture
+                .timeout()
+                .ok_or_else(|| eyre!("Timed out before code started"));
         }
-
-        last_pulse = pulse_length;
-    }
-
-    debug!("Reading pulses...");
-    while let Some(event) = rx_pin.poll_interrupt(false, Some(MAX_PULSE_LENGTH))? {
-        let timestamp = Instant::now();
-        let pulse_length = timestamp - last_timestamp;
-        pulses.push(
-            pulse_length
-                .as_micros()
-                .try_into()
-                .context("Pulse length too long")?,
-        );
-        if pulse_length > BREAK_PULSE_LENGTH {
-            debug!(
-                "Found final {:?} break pulse {} μs.",
-                previous_level(&event),
-                pulse_length.as_micros(),
-            );
-            break;
-        }
-        last_timestamp = timestamp;
-    }
-
-    Ok(pulses)
-}
-
-/// Given an interreupt, returns the level of the pin before the interrupt trigger.
-fn previous_level(event: &Event) -> Level {
-    if event.trigger == Trigger::RisingEdge {
-        Level::Low
-    } else {
-        Level::High
     }
 }
@@ -0,0 +1,65 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Decodes codes from a bare superheterodyne receiver module such as an RXB6, with its data pin
+//! connected directly to a GPIO pin on a Raspberry Pi. No radio configuration is needed.
+
+use eyre::{eyre, Report};
+use log::{debug, trace};
+use rfbutton::{decode_with, DecodeOptions, EdgeCapture};
+use rppal::gpio::{Gpio, InputPin, Trigger};
+use std::time::{Duration, Instant};
+
+/// The GPIO pin to which the 433 MHz receiver's data pin is connected.
+const RX_PIN: u8 = 27;
+
+const MAX_PULSE_LENGTH: Duration = Duration::from_millis(10);
+const BREAK_PULSE_LENGTH: Duration = Duration::from_millis(7);
+
+fn main() -> Result<(), Report> {
+    color_eyre::install()?;
+    pretty_env_logger::init();
+
+    let gpio = Gpio::new()?;
+    let mut rx_pin = gpio.get(RX_PIN)?.into_input();
+    rx_pin.set_interrupt(Trigger::Both, None)?;
+
+    // Use the same break pulse length for decoding as for capturing.
+    let options = DecodeOptions {
+        break_pulse_length: BREAK_PULSE_LENGTH.as_micros().try_into()?,
+        ..Default::default()
+    };
+
+    println!("Waiting for codes...");
+    loop {
+        match receive(&mut rx_pin) {
+            Ok(pulses) => match decode_with(&pulses, &options) {
+                Ok(code) if code.length > 0 => println!("Decoded: {:?}", code),
+                Ok(_) => debug!("Decoded 0 bits."),
+                Err(e) => debug!("Decode error: {}", e),
+            },
+            Err(e) => debug!("Receive error: {}", e),
+        }
+    }
+}
+
+/// Wait for a single code.
+fn receive(rx_pin: &mut InputPin) -> Result<Vec<u16>, Report> {
+    let mut capture = EdgeCapture::with_break_pulse_length(Instant::now(), BREAK_PULSE_LENGTH);
+    loop {
+        // Wait for as long as it takes for the start of a code, but then only for the maximum pulse
+        // length.
+        let timeout = capture.is_capturing().then_some(MAX_PULSE_LENGTH);
+        if let Some(event) = rx_pin.poll_interrupt(false, timeout)? {
+            trace!("{:?}", event.trigger);
+            if let Some(pulses) = capture.edge(event.trigger == Trigger::RisingEdge) {
+                return Ok(pulses);
+            }
+        } else {
+            return capture
+                .timeout()
+                .ok_or_else(|| eyre!("Timed out before code started"));
+        }
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Checks that rfbutton can decode codes in a `#![no_std]` binary without `alloc`, for targets
+//! without a heap. This is built for a bare metal target in CI; it doesn't need to run.
+
+#![no_std]
+#![no_main]
+
+use core::panic::PanicInfo;
+use rfbutton::{decode, DecodeOptions, PulseDecoder};
+
+/// Decodes the given pulses both at once and as a stream, returning the length of the code if
+/// they agree.
+#[no_mangle]
+pub extern "C" fn decode_pulses(pulses: &[u16; 8]) -> u8 {
+    let mut decoder: PulseDecoder = PulseDecoder::new(DecodeOptions::default());
+    let streamed = pulses
+        .iter()
+        .filter_map(|&pulse| decoder.feed(pulse))
+        .next();
+    match decode(pulses) {
+        Ok(code) if streamed == Some(code) => code.length,
+        _ => 0,
+    }
+}
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    loop {}
+}
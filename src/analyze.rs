@@ -0,0 +1,142 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{pulse_clusters, round_div, DecodeOptions, PulseCluster};
+use alloc::vec::Vec;
+
+/// Clusters with less than this percentage of all the pulses are treated as noise when looking for
+/// the short and long pulses.
+const MIN_CLUSTER_PERCENT: usize = 5;
+
+/// Pulses more than this many times the short pulse duration are treated as breaks.
+const MIN_BREAK_MULTIPLE: u32 = 8;
+
+/// The timing of an unknown remote inferred from a capture, returned by [`analyze`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Analysis {
+    /// Groups of pulses of similar lengths, in increasing order of length.
+    pub clusters: Vec<PulseCluster>,
+    /// The likely short pulse duration in microseconds, from the shortest cluster which isn't
+    /// noise.
+    pub short_duration: Option<u16>,
+    /// A likely minimum length in microseconds for break pulses, halfway between the longest data
+    /// pulses and the shortest breaks.
+    pub break_pulse_length: Option<u16>,
+    /// The likely length of the long pulses as a multiple of the short pulse duration, such as 3
+    /// for the default symbols.
+    pub long_multiple: Option<u8>,
+    /// Options to try decoding the capture with, based on the other inferences.
+    ///
+    /// The short pulse duration is still estimated for each frame, as it may drift between button
+    /// presses.
+    pub options: DecodeOptions,
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse) from an unknown
+/// remote, infers its timing and suggests options to decode it with.
+///
+/// The pulses are grouped into clusters of similar lengths. The shortest cluster which isn't noise
+/// is taken to be the short pulse, the most common longer cluster to be the long pulse, and
+/// clusters more than 8 times as long as the short pulse to be breaks. A 1 is assumed to be a long
+/// high pulse followed by a short low pulse, and a 0 the opposite.
+pub fn analyze(pulses: &[u16]) -> Analysis {
+    let clusters = pulse_clusters(pulses);
+    let significant =
+        |cluster: &&PulseCluster| cluster.count * 100 >= pulses.len() * MIN_CLUSTER_PERCENT;
+    let short_duration = clusters
+        .iter()
+        .find(significant)
+        .map(|cluster| cluster.mean)
+        .filter(|&mean| mean != 0);
+    let mut long_multiple = None;
+    let mut break_pulse_length = None;
+    let mut options = DecodeOptions::default();
+    if let Some(short_duration) = short_duration {
+        let (data, breaks): (Vec<&PulseCluster>, Vec<_>) = clusters.iter().partition(|cluster| {
+            u32::from(cluster.mean) <= u32::from(short_duration) * MIN_BREAK_MULTIPLE
+        });
+        long_multiple = data
+            .iter()
+            .copied()
+            .filter(|cluster| cluster.mean > short_duration)
+            .filter(significant)
+            .max_by_key(|cluster| cluster.count)
+            .map(|cluster| round_div(cluster.mean.into(), short_duration.into()))
+            .filter(|&multiple| multiple > 1)
+            .and_then(|multiple| u8::try_from(multiple).ok());
+        if let (Some(longest_data), Some(shortest_break)) = (data.last(), breaks.first()) {
+            break_pulse_length =
+                Some(((u32::from(longest_data.mean) + u32::from(shortest_break.mean)) / 2) as u16);
+        }
+    }
+
+    if let Some(long_multiple) = long_multiple {
+        options.one = (long_multiple, 1);
+        options.zero = (1, long_multiple);
+    }
+    if let Some(break_pulse_length) = break_pulse_length {
+        options.break_pulse_length = break_pulse_length;
+    }
+    Analysis {
+        clusters,
+        short_duration,
+        break_pulse_length,
+        long_multiple,
+        options,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode, decode_with, Code, Error};
+
+    #[test]
+    fn default_timing() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        let pulses = code.repeat_pulses(320, 5, 9920);
+        let analysis = analyze(&pulses);
+        assert_eq!(analysis.short_duration, Some(320));
+        assert_eq!(analysis.long_multiple, Some(3));
+        assert_eq!(analysis.break_pulse_length, Some(5440));
+        assert_eq!(
+            analysis.clusters.last(),
+            Some(&PulseCluster {
+                mean: 9920,
+                count: 6
+            })
+        );
+        assert_eq!(decode_with(&pulses, &analysis.options), Ok(code));
+    }
+
+    #[test]
+    fn short_breaks() {
+        // The breaks are shorter than the default break pulse length.
+        let frame = [100, 2000, 300, 100, 100, 300, 300, 100, 300, 100];
+        let pulses = frame.repeat(3);
+        assert_eq!(decode(&pulses), Err(Error::NoStart));
+        let analysis = analyze(&pulses);
+        assert_eq!(analysis.short_duration, Some(100));
+        assert_eq!(analysis.long_multiple, Some(3));
+        assert_eq!(analysis.break_pulse_length, Some(1150));
+        assert_eq!(
+            decode_with(&pulses, &analysis.options),
+            Ok(Code {
+                value: 0b1011,
+                length: 4
+            })
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let analysis = analyze(&[]);
+        assert_eq!(analysis.clusters, vec![]);
+        assert_eq!(analysis.short_duration, None);
+        assert_eq!(analysis.options, DecodeOptions::default());
+    }
+}
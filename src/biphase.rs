@@ -0,0 +1,152 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{apply_bit_order, next_start, round_div, verify_checksum, Code, DecodeOptions, Error};
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a button code which uses bi-phase mark encoding, also known as differential Manchester.
+///
+/// There is a transition at the start of every bit, and a 1 also has a transition in the middle.
+/// So a 0 is a single pulse of a full bit duration, and a 1 is two pulses each of half the bit
+/// duration, regardless of their levels. The frame ends at the next break. If the break starts in
+/// the middle of a 1 then the second half of the 1 is taken to be part of the break, but a 0 at
+/// the end with the same level as the break can't be distinguished from it so is lost.
+///
+/// The half bit duration is `options.short_duration` if it is set, or is otherwise estimated from
+/// the shortest pulses of the frame. This means that a frame without any 1 bits can only be decoded
+/// if `options.short_duration` is set. The start detection, break pulse length, minimum length, bit
+/// order and checksum from `options` are also used; the symbol multiples are not.
+///
+/// Returns [`Error::InvalidPulseLength`] with the invalid pulse and the one following it if a pulse
+/// isn't about a half or a full bit duration, or a half bit pulse isn't followed by another.
+pub fn decode_biphase(pulses: &[u16], options: &DecodeOptions) -> Result<Code, Error> {
+    if pulses.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    let start = next_start(pulses, 0, options, 1).ok_or(Error::NoStart)?;
+    let frame = &pulses[start..];
+    let end = frame
+        .iter()
+        .position(|&pulse| pulse > options.break_pulse_length)
+        .unwrap_or(frame.len());
+    if end == 0 {
+        return Err(Error::TooShort);
+    }
+    let half_bit = half_bit_duration(&frame[..end], options).ok_or(Error::NoSignal)?;
+
+    let mut value = 0;
+    let mut length: u8 = 0;
+    let mut i = 0;
+    while i < end {
+        let pulse = frame[i];
+        let next = frame.get(i + 1).copied().unwrap_or(0);
+        let bit = match round_div(pulse.into(), half_bit) {
+            2 => {
+                i += 1;
+                false
+            }
+            // The second half of the last 1 may be part of the break.
+            1 if i + 1 == end || round_div(next.into(), half_bit) == 1 => {
+                i += 2;
+                true
+            }
+            _ => return Err(Error::InvalidPulseLength(pulse, next)),
+        };
+        if u32::from(length) >= u128::BITS {
+            return Err(Error::TooLong);
+        }
+        value = value << 1 | u128::from(bit);
+        length += 1;
+    }
+
+    if length < options.min_bits {
+        return Err(Error::TooShort);
+    }
+    verify_checksum(apply_bit_order(Code { value, length }, options), options)
+}
+
+/// Returns the half bit duration to decode the given frame with, or `None` if it can't be
+/// estimated.
+///
+/// Unless it is set in the options, it is estimated as the average of each pulse divided by whether
+/// it is about one or two times the shortest pulse.
+fn half_bit_duration(frame: &[u16], options: &DecodeOptions) -> Option<u64> {
+    if let Some(short_duration) = options.short_duration {
+        return Some(short_duration.into()).filter(|&duration| duration != 0);
+    }
+    let shortest = u64::from(*frame.iter().min()?);
+    if shortest == 0 {
+        return None;
+    }
+    let (sum, count) = frame
+        .iter()
+        .filter_map(|&pulse| {
+            let pulse = u64::from(pulse);
+            match round_div(pulse, shortest) {
+                multiple @ (1 | 2) => Some(pulse / multiple),
+                _ => None,
+            }
+        })
+        .fold((0, 0), |(sum, count), half_bit| (sum + half_bit, count + 1));
+    Some(sum / count).filter(|&duration| duration != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_bits() {
+        // Half bit duration of 400 μs, with some jitter.
+        let pulses = [
+            300, 10000, 410, 390, 820, 380, 420, 400, 410, 790, 810, 390, 400, 420, 380, 10000,
+        ];
+        assert_eq!(
+            decode_biphase(&pulses, &DecodeOptions::default()),
+            Ok(Code {
+                value: 0b10110011,
+                length: 8
+            })
+        );
+    }
+
+    #[test]
+    fn last_one_merged_with_break() {
+        let pulses = [300, 10000, 800, 400, 400, 800, 400, 10400];
+        assert_eq!(
+            decode_biphase(&pulses, &DecodeOptions::default()),
+            Ok(Code {
+                value: 0b0101,
+                length: 4
+            })
+        );
+    }
+
+    #[test]
+    fn no_ones() {
+        let pulses = [300, 10000, 800, 800, 800, 10000];
+        assert_eq!(
+            decode_biphase(
+                &pulses,
+                &DecodeOptions {
+                    short_duration: Some(400),
+                    ..Default::default()
+                }
+            ),
+            Ok(Code {
+                value: 0b000,
+                length: 3
+            })
+        );
+    }
+
+    #[test]
+    fn lone_half_bit() {
+        let pulses = [300, 10000, 400, 400, 400, 800, 400, 400, 10000];
+        assert_eq!(
+            decode_biphase(&pulses, &DecodeOptions::default()),
+            Err(Error::InvalidPulseLength(400, 800))
+        );
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! A compact binary format for pulse durations, such as for sending captures between machines.
+//!
+//! Each pulse duration in microseconds is 2 bytes, as a little-endian `u16`, with no header or
+//! separators. The first pulse is high, as for [`decode`](crate::decode).
+
+use crate::Error;
+use alloc::vec::Vec;
+
+/// Parses pulse durations from the binary format, with each pulse as a little-endian `u16`.
+///
+/// Returns [`Error::OddByteLength`] if there are an odd number of bytes, as the last pulse is
+/// incomplete. When reading from a stream, keep any odd byte at the end of one chunk to add to the
+/// start of the next.
+pub fn pulses_from_bytes(bytes: &[u8]) -> Result<Vec<u16>, Error> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(Error::OddByteLength(bytes.len()));
+    }
+    Ok(bytes
+        .chunks_exact(2)
+        .map(|pulse| u16::from_le_bytes([pulse[0], pulse[1]]))
+        .collect())
+}
+
+/// Converts pulse durations to the binary format, with each pulse as a little-endian `u16`.
+pub fn pulses_to_bytes(pulses: &[u16]) -> Vec<u8> {
+    pulses
+        .iter()
+        .flat_map(|pulse| pulse.to_le_bytes())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let pulses = [320, 9920, 960, 320, 0, u16::MAX];
+        let bytes = pulses_to_bytes(&pulses);
+        assert_eq!(
+            bytes,
+            [0x40, 0x01, 0xc0, 0x26, 0xc0, 0x03, 0x40, 0x01, 0x00, 0x00, 0xff, 0xff]
+        );
+        assert_eq!(pulses_from_bytes(&bytes), Ok(pulses.to_vec()));
+        assert_eq!(pulses_from_bytes(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn odd_length() {
+        assert_eq!(
+            pulses_from_bytes(&[0x40, 0x01, 0xc0]),
+            Err(Error::OddByteLength(3))
+        );
+    }
+}
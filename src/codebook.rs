@@ -0,0 +1,144 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::Code;
+use std::collections::HashMap;
+
+/// A table of known codes and labels for them, such as the names of the buttons which send them.
+///
+/// With the `serde` feature this can be deserialized from a map of codes in their usual hex string
+/// form to labels, such as a TOML table like:
+///
+/// ```toml
+/// 48b2a4 = "Doorbell"
+/// 48b2a8 = "Garage"
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CodeBook {
+    labels: HashMap<Code, String>,
+}
+
+impl CodeBook {
+    /// Creates a new empty code book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the given label for the given code, returning the previous label for it if any.
+    pub fn insert(&mut self, code: Code, label: String) -> Option<String> {
+        self.labels.insert(code, label)
+    }
+
+    /// Returns the label for the given code, if it is known.
+    ///
+    /// Codes must match exactly, including their length.
+    pub fn lookup(&self, code: &Code) -> Option<&str> {
+        self.labels.get(code).map(String::as_str)
+    }
+}
+
+impl FromIterator<(Code, String)> for CodeBook {
+    fn from_iter<I: IntoIterator<Item = (Code, String)>>(iter: I) -> Self {
+        Self {
+            labels: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CodeBook {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Self {
+            labels: HashMap::deserialize(deserializer)?,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CodeBook {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.labels.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let book: CodeBook = [(
+            Code {
+                value: 0x48b2a4,
+                length: 24,
+            },
+            "Doorbell".to_string(),
+        )]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            book.lookup(&Code {
+                value: 0x48b2a4,
+                length: 24
+            }),
+            Some("Doorbell")
+        );
+        assert_eq!(
+            book.lookup(&Code {
+                value: 0x48b2a4,
+                length: 28
+            }),
+            None
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_codebook() {
+        use serde_test::{assert_de_tokens, assert_tokens, Configure, Token};
+
+        let mut book = CodeBook::new();
+        book.insert(
+            Code {
+                value: 0x48b2a4,
+                length: 24,
+            },
+            "Doorbell".to_string(),
+        );
+        assert_tokens(
+            &book.clone().readable(),
+            &[
+                Token::Map { len: Some(1) },
+                Token::Str("48b2a4"),
+                Token::Str("Doorbell"),
+                Token::MapEnd,
+            ],
+        );
+
+        book.insert(
+            Code {
+                value: 0x0a,
+                length: 8,
+            },
+            "Garage".to_string(),
+        );
+        assert_de_tokens(
+            &book.readable(),
+            &[
+                Token::Map { len: Some(2) },
+                Token::Str("0a"),
+                Token::Str("Garage"),
+                Token::Str("48b2a4"),
+                Token::Str("Doorbell"),
+                Token::MapEnd,
+            ],
+        );
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::Code;
+use alloc::{format, string::String};
+use core::fmt::Write;
+
+/// The number of pulse durations on each line of generated source code.
+const PULSES_PER_LINE: usize = 12;
+
+/// A programming language to generate source code in.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SourceLanguage {
+    /// A Rust `const` array of `u16`.
+    Rust,
+    /// A C `const` array of `uint16_t`, from `<stdint.h>`.
+    C,
+}
+
+impl Code {
+    /// Generates source code for a constant array of the pulses to transmit the code, such as to
+    /// embed in firmware for a microcontroller.
+    ///
+    /// The pulses are exactly those returned by [`to_pulses`](Self::to_pulses) for the given short
+    /// pulse duration, in microseconds starting with a high pulse. `name` is used as the name of
+    /// the constant as it is, so must be a valid identifier in the language.
+    pub fn to_source(&self, short_duration: u16, name: &str, language: SourceLanguage) -> String {
+        let pulses = self.to_pulses(short_duration);
+        let mut source = match language {
+            SourceLanguage::Rust => format!("const {}: [u16; {}] = [\n", name, pulses.len()),
+            SourceLanguage::C => format!("const uint16_t {}[{}] = {{\n", name, pulses.len()),
+        };
+        for line in pulses.chunks(PULSES_PER_LINE) {
+            source.push_str("   ");
+            for pulse in line {
+                write!(source, " {},", pulse).unwrap();
+            }
+            source.push('\n');
+        }
+        source.push_str(match language {
+            SourceLanguage::Rust => "];\n",
+            SourceLanguage::C => "};\n",
+        });
+        source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: Code = Code {
+        value: 0b1011,
+        length: 4,
+    };
+
+    #[test]
+    fn rust() {
+        assert_eq!(
+            CODE.to_source(300, "DOORBELL", SourceLanguage::Rust),
+            "const DOORBELL: [u16; 12] = [
+    300, 9300, 900, 300, 300, 900, 900, 300, 900, 300, 300, 9300,
+];
+"
+        );
+    }
+
+    #[test]
+    fn c() {
+        assert_eq!(
+            Code {
+                value: 0x1ff,
+                length: 9
+            }
+            .to_source(300, "garage", SourceLanguage::C),
+            "const uint16_t garage[22] = {
+    300, 9300, 900, 300, 900, 300, 900, 300, 900, 300, 900, 300,
+    900, 300, 900, 300, 900, 300, 900, 300, 300, 9300,
+};
+"
+        );
+    }
+}
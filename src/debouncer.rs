@@ -0,0 +1,74 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::Code;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Suppresses repeated decodes of the same code, such as while a button is held down.
+///
+/// Each code is tracked separately, so pressing one button doesn't suppress another.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Debouncer {
+    window: Duration,
+    last_seen: HashMap<Code, Instant>,
+}
+
+impl Debouncer {
+    /// Creates a new debouncer which suppresses a code if it was last seen less than `window` ago.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            last_seen: HashMap::new(),
+        }
+    }
+
+    /// Records that the given code was decoded at the given time, and returns whether it should be
+    /// handled.
+    ///
+    /// Returns `false` if the same code was seen less than the window ago, or `true` otherwise.
+    /// Suppressed codes still count as being seen, so a code which keeps repeating more often than
+    /// the window is only accepted once.
+    pub fn accept(&mut self, code: Code, now: Instant) -> bool {
+        let window = self.window;
+        // Forget codes which can no longer be suppressed, so the map doesn't keep growing.
+        self.last_seen
+            .retain(|_, last_seen| now.saturating_duration_since(*last_seen) < window);
+        self.last_seen.insert(code, now).is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suppress_repeats() {
+        let a = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        let b = Code {
+            value: 0x48b2a8,
+            length: 24,
+        };
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut debouncer = Debouncer::new(Duration::from_millis(500));
+
+        assert!(debouncer.accept(a, at(0)));
+        assert!(!debouncer.accept(a, at(100)));
+        // Another code isn't suppressed, and doesn't stop the first being suppressed.
+        assert!(debouncer.accept(b, at(200)));
+        assert!(!debouncer.accept(a, at(300)));
+        // The buttons are still held, so these are less than the window after they were last seen.
+        assert!(!debouncer.accept(b, at(600)));
+        assert!(!debouncer.accept(a, at(700)));
+        // After a gap of at least the window, they are accepted again.
+        assert!(debouncer.accept(a, at(1200)));
+        assert!(debouncer.accept(b, at(1200)));
+    }
+}
@@ -0,0 +1,316 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Tri-state codes of PT2262 and compatible encoders, and conversion to and from their dip-switch
+//! settings.
+//!
+//! Each address or data pin of a PT2262 can be tied low, tied high or left floating, and is sent as
+//! a pair of bits: `00` for low, `11` for high and `01` for floating. The setting is
+//! conventionally written as one character per pin, `0`, `1` or `F`, such as `"0F0FFF"`, which is
+//! what users can read off the switches of a remote.
+
+use crate::{Code, Error};
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+use core::{
+    fmt::{self, Debug, Display, Formatter},
+    str::FromStr,
+};
+
+/// The maximum number of tri-state positions which fit in a [`Code`].
+const MAX_POSITIONS: usize = 64;
+
+/// The setting of a single address or data pin of a tri-state encoder.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum TriState {
+    /// Tied low, sent as `00` and written as `0`.
+    Low,
+    /// Tied high, sent as `11` and written as `1`.
+    High,
+    /// Left floating, sent as `01` and written as `F`.
+    Floating,
+}
+
+impl TriState {
+    /// Returns the character for the state in a dip-switch setting: `0`, `1` or `F`.
+    pub fn to_char(self) -> char {
+        match self {
+            Self::Low => '0',
+            Self::High => '1',
+            Self::Floating => 'F',
+        }
+    }
+
+    /// Parses a `0`, `1` or `F` (or `f`) character, returning `None` for any other character.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '0' => Some(Self::Low),
+            '1' => Some(Self::High),
+            'F' | 'f' => Some(Self::Floating),
+            _ => None,
+        }
+    }
+
+    /// Returns the pair of bits which the state is sent as.
+    fn bits(self) -> u128 {
+        match self {
+            Self::Low => 0b00,
+            Self::High => 0b11,
+            Self::Floating => 0b01,
+        }
+    }
+
+    /// Returns the state sent as the given pair of bits, or `None` for `10`.
+    fn from_bits(bits: u128) -> Option<Self> {
+        match bits {
+            0b00 => Some(Self::Low),
+            0b11 => Some(Self::High),
+            0b01 => Some(Self::Floating),
+            _ => None,
+        }
+    }
+}
+
+/// A code from a PT2262 or compatible tri-state encoder, as the setting of each of its pins.
+///
+/// This is formatted and parsed as a dip-switch setting such as `"0F0FFF"`, as used by the
+/// `sendTriState` function of the Arduino rc-switch library, and converts to and from a [`Code`]
+/// with two bits for each pin.
+#[derive(Copy, Clone, Eq, Hash, PartialEq)]
+pub struct TriStateCode {
+    /// The bits of the code, which always have an even length with no `10` pairs.
+    code: Code,
+}
+
+impl TriStateCode {
+    /// Creates a tri-state code from the settings of each pin, starting from the first sent.
+    ///
+    /// Returns [`Error::TooLong`] if there are more than 64 pins.
+    pub fn new(states: &[TriState]) -> Result<Self, Error> {
+        if states.len() > MAX_POSITIONS {
+            return Err(Error::TooLong);
+        }
+        Ok(Self {
+            code: Code {
+                value: states
+                    .iter()
+                    .fold(0, |value, state| value << 2 | state.bits()),
+                length: 2 * states.len() as u8,
+            },
+        })
+    }
+
+    /// Returns the number of pins.
+    pub fn len(&self) -> usize {
+        usize::from(self.code.length / 2)
+    }
+
+    /// Returns whether the code has no pins.
+    pub fn is_empty(&self) -> bool {
+        self.code.length == 0
+    }
+
+    /// Returns the setting of the pin at the given index, starting from the first sent, or `None`
+    /// if it is out of range.
+    pub fn get(&self, index: usize) -> Option<TriState> {
+        let position = self.len().checked_sub(index + 1)?;
+        TriState::from_bits(self.code.field(2 * position as u8, 2))
+    }
+
+    /// Returns an iterator over the settings of the pins, starting from the first sent.
+    pub fn iter(&self) -> impl Iterator<Item = TriState> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+}
+
+impl TryFrom<Code> for TriStateCode {
+    type Error = Error;
+
+    /// Returns [`Error::NotTriState`] if the code has an odd length or contains a `10` pair, as it
+    /// can't have come from a tri-state encoder.
+    fn try_from(code: Code) -> Result<Self, Error> {
+        if !code.length.is_multiple_of(2)
+            || (0..code.length / 2)
+                .any(|position| TriState::from_bits(code.field(2 * position, 2)).is_none())
+        {
+            return Err(Error::NotTriState);
+        }
+        Ok(Self { code })
+    }
+}
+
+impl From<TriStateCode> for Code {
+    fn from(code: TriStateCode) -> Self {
+        code.code
+    }
+}
+
+/// Formats the code as a dip-switch setting with one `0`, `1` or `F` for each pin.
+impl Display for TriStateCode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for state in self.iter() {
+            write!(f, "{}", state.to_char())?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for TriStateCode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("TriStateCode")
+            .field(&format_args!("{}", self))
+            .finish()
+    }
+}
+
+impl FromStr for TriStateCode {
+    type Err = Error;
+
+    /// Parses a dip-switch setting of `0`, `1` and `F` (or `f`) characters.
+    ///
+    /// Returns [`Error::NotTriState`] if the string contains any other characters, or
+    /// [`Error::TooLong`] if it has more than 64 positions.
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let mut code = Code {
+            value: 0,
+            length: 0,
+        };
+        for (i, position) in s.chars().enumerate() {
+            if i >= MAX_POSITIONS {
+                return Err(Error::TooLong);
+            }
+            let state = TriState::from_char(position).ok_or(Error::NotTriState)?;
+            code.value = code.value << 2 | state.bits();
+            code.length += 2;
+        }
+        Ok(Self { code })
+    }
+}
+
+impl Code {
+    /// Formats the code as a dip-switch setting with one `0`, `1` or `F` for each pair of bits,
+    /// from the most significant.
+    ///
+    /// Returns `None` if the code has an odd length or contains a `10` pair, as it can't have come
+    /// from a tri-state encoder.
+    #[cfg(feature = "alloc")]
+    pub fn to_dipswitch_string(&self) -> Option<String> {
+        TriStateCode::try_from(*self)
+            .ok()
+            .map(|code| code.to_string())
+    }
+
+    /// Parses a dip-switch setting of `0`, `1` and `F` (or `f`) characters into a code with two
+    /// bits for each position, as [`to_dipswitch_string`](Self::to_dipswitch_string) formats it.
+    ///
+    /// Returns `None` if the string contains any other characters, or more than 64 positions.
+    pub fn from_dipswitch_string(s: &str) -> Option<Code> {
+        s.parse::<TriStateCode>().ok().map(Code::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn round_trip() {
+        let code = Code {
+            value: 0b00_01_00_01_01_01,
+            length: 12,
+        };
+        assert_eq!(code.to_dipswitch_string().as_deref(), Some("0F0FFF"));
+        assert_eq!(Code::from_dipswitch_string("0F0FFF"), Some(code));
+        assert_eq!(Code::from_dipswitch_string("0f0fff"), Some(code));
+
+        let code = Code::from_dipswitch_string("1F01F0FF10F1").unwrap();
+        assert_eq!(code.length, 24);
+        assert_eq!(code.to_dipswitch_string().as_deref(), Some("1F01F0FF10F1"));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn not_tri_state() {
+        let code = Code {
+            value: 0b0010,
+            length: 4,
+        };
+        assert_eq!(code.to_dipswitch_string(), None);
+        let code = Code {
+            value: 0b011,
+            length: 3,
+        };
+        assert_eq!(code.to_dipswitch_string(), None);
+        assert_eq!(Code::from_dipswitch_string("0F2"), None);
+        assert_eq!(Code::from_dipswitch_string(&"F".repeat(65)), None);
+        assert_eq!(
+            Code::from_dipswitch_string(&"1".repeat(64)),
+            Some(Code {
+                value: u128::MAX,
+                length: 128
+            })
+        );
+    }
+
+    #[test]
+    fn tri_state_code() {
+        let tri_state: TriStateCode = "0F1f".parse().unwrap();
+        assert_eq!(tri_state.len(), 4);
+        assert_eq!(
+            tri_state.iter().collect::<Vec<_>>(),
+            vec![
+                TriState::Low,
+                TriState::Floating,
+                TriState::High,
+                TriState::Floating
+            ]
+        );
+        assert_eq!(tri_state.get(2), Some(TriState::High));
+        assert_eq!(tri_state.get(4), None);
+        assert_eq!(tri_state.to_string(), "0F1F");
+        assert_eq!(format!("{:?}", tri_state), "TriStateCode(0F1F)");
+        assert_eq!(
+            TriStateCode::new(&[
+                TriState::Low,
+                TriState::Floating,
+                TriState::High,
+                TriState::Floating
+            ]),
+            Ok(tri_state)
+        );
+
+        let code = Code::from(tri_state);
+        assert_eq!(
+            code,
+            Code {
+                value: 0b00_01_11_01,
+                length: 8
+            }
+        );
+        assert_eq!(TriStateCode::try_from(code), Ok(tri_state));
+    }
+
+    #[test]
+    fn invalid_tri_state_code() {
+        assert_eq!(
+            TriStateCode::try_from(Code {
+                value: 0b0010,
+                length: 4
+            }),
+            Err(Error::NotTriState)
+        );
+        assert_eq!("0F2".parse::<TriStateCode>(), Err(Error::NotTriState));
+        assert_eq!("F".repeat(65).parse::<TriStateCode>(), Err(Error::TooLong));
+        assert_eq!(TriStateCode::new(&[TriState::Low; 65]), Err(Error::TooLong));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn empty() {
+        let code = Code::from_dipswitch_string("").unwrap();
+        assert_eq!(code.length, 0);
+        assert_eq!(code.to_dipswitch_string().as_deref(), Some(""));
+    }
+}
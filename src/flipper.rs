@@ -0,0 +1,178 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Conversion to and from the `.sub` files used by the Sub-GHz app of the Flipper Zero.
+//!
+//! A RAW `.sub` file is a text file of `Key: value` lines. After the header, each `RAW_Data` line
+//! lists pulse durations in microseconds, with high pulses positive and low pulses negative.
+
+use crate::{Code, SYNC_MULTIPLE};
+use std::{
+    fmt::Write,
+    io::{self, BufRead, BufReader, Read},
+};
+
+/// The preset for on-off keying with a 650 kHz bandwidth, which the Flipper Zero uses by default
+/// for 433 MHz remotes.
+pub const FLIPPER_OOK_PRESET: &str = "FuriHalSubGhzPresetOok650Async";
+
+/// The maximum number of pulse durations on each `RAW_Data` line, as the Flipper Zero writes them.
+const PULSES_PER_LINE: usize = 512;
+
+impl Code {
+    /// Formats the code as a Flipper Zero RAW `.sub` file, to transmit it `repeats` times with the
+    /// given short pulse duration in microseconds.
+    ///
+    /// The pulses are the same as [`repeat_pulses`](Self::repeat_pulses) returns, with sync breaks
+    /// of the same length as [`to_pulses`](Self::to_pulses) uses. `frequency` is in Hz, such as
+    /// `433920000`, and `preset` is the name of the Flipper Zero's radio preset to use, such as
+    /// [`FLIPPER_OOK_PRESET`].
+    pub fn to_flipper_sub(
+        &self,
+        short_duration: u16,
+        repeats: usize,
+        frequency: u32,
+        preset: &str,
+    ) -> String {
+        let pulses = self.repeat_pulses(
+            short_duration,
+            repeats,
+            short_duration.saturating_mul(SYNC_MULTIPLE),
+        );
+        let mut sub = format!(
+            "Filetype: Flipper SubGhz RAW File\nVersion: 1\nFrequency: {}\nPreset: {}\n\
+             Protocol: RAW\n",
+            frequency, preset
+        );
+        for line in pulses.chunks(PULSES_PER_LINE) {
+            sub.push_str("RAW_Data:");
+            for (i, pulse) in line.iter().enumerate() {
+                // Full lines have an even number of pulses, so every line starts with a high pulse.
+                let sign = if i.is_multiple_of(2) { "" } else { "-" };
+                write!(sub, " {}{}", sign, pulse).unwrap();
+            }
+            sub.push('\n');
+        }
+        sub
+    }
+}
+
+/// Reads the pulses of a Flipper Zero RAW `.sub` file, such as a capture saved by its Sub-GHz app.
+///
+/// The pulse durations from all the `RAW_Data` lines are returned in microseconds starting with a
+/// high pulse, as for [`decode`](crate::decode). Consecutive durations with the same level are
+/// merged, any low pulse before the first high pulse is dropped, and pulses too long for a `u16`
+/// are clamped to `u16::MAX` so that they are still treated as breaks. Other lines are ignored.
+///
+/// Returns an error with [`io::ErrorKind::InvalidData`] if a `RAW_Data` line contains anything
+/// other than integers.
+pub fn pulses_from_sub<R: Read>(r: R) -> io::Result<Vec<u16>> {
+    let mut pulses = Vec::new();
+    // The duration of the current pulse so far, and whether it is high.
+    let mut current: Option<(u32, bool)> = None;
+    for line in BufReader::new(r).lines() {
+        let line = line?;
+        let Some(data) = line.trim().strip_prefix("RAW_Data:") else {
+            continue;
+        };
+        for value in data.split_whitespace() {
+            let value = value.parse::<i32>().map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid RAW_Data value {:?}: {}", value, e),
+                )
+            })?;
+            let (duration, high) = (value.unsigned_abs(), value > 0);
+            match &mut current {
+                _ if value == 0 => {}
+                Some((current_duration, current_high)) if *current_high == high => {
+                    *current_duration = current_duration.saturating_add(duration);
+                }
+                // Drop any low pulse at the start.
+                None if !high => {}
+                _ => {
+                    if let Some((duration, _)) = current {
+                        pulses.push(duration.try_into().unwrap_or(u16::MAX));
+                    }
+                    current = Some((duration, high));
+                }
+            }
+        }
+    }
+    if let Some((duration, _)) = current {
+        pulses.push(duration.try_into().unwrap_or(u16::MAX));
+    }
+    Ok(pulses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_file() {
+        let code = Code {
+            value: 0b10,
+            length: 2,
+        };
+        assert_eq!(
+            code.to_flipper_sub(300, 2, 433920000, FLIPPER_OOK_PRESET),
+            "Filetype: Flipper SubGhz RAW File
+Version: 1
+Frequency: 433920000
+Preset: FuriHalSubGhzPresetOok650Async
+Protocol: RAW
+RAW_Data: 300 -9300 900 -300 300 -900 300 -9300 900 -300 300 -900 300 -9300
+"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        let sub = code.to_flipper_sub(320, 20, 433920000, FLIPPER_OOK_PRESET);
+        let pulses = pulses_from_sub(sub.as_bytes()).unwrap();
+        assert_eq!(pulses, code.repeat_pulses(320, 20, 9920));
+        assert_eq!(crate::decode(&pulses), Ok(code));
+    }
+
+    #[test]
+    fn merge_and_clamp() {
+        let sub = "Filetype: Flipper SubGhz RAW File
+Protocol: RAW
+RAW_Data: -500 300 -9000 -40000 900 -300 300
+RAW_Data: 200 -900 0 -100 300 -70000
+";
+        assert_eq!(
+            pulses_from_sub(sub.as_bytes()).unwrap(),
+            vec![300, 49000, 900, 300, 500, 1000, 300, u16::MAX]
+        );
+    }
+
+    #[test]
+    fn invalid_data() {
+        let error = pulses_from_sub("RAW_Data: 300 -9300 abc".as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn split_lines() {
+        let code = Code {
+            value: 0xffff_ffff,
+            length: 32,
+        };
+        let sub = code.to_flipper_sub(300, 10, 433920000, FLIPPER_OOK_PRESET);
+        let lines = sub
+            .lines()
+            .filter_map(|line| line.strip_prefix("RAW_Data: "))
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].split(' ').count(), 512);
+        assert!(lines[1].starts_with("900 -300 "));
+        assert!(lines[1].ends_with(" 300 -9300"));
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use alloc::{vec, vec::Vec};
+use core::mem::{replace, take};
+
+/// Splits a stream of pulses into frames at break pulses, as the pulses arrive.
+///
+/// Each frame returned starts with the break pulse before it and ends with the break pulse after
+/// it, so it can be passed straight to [`decode`](crate::decode).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FrameSplitter {
+    break_pulse_length: u16,
+    max_length: usize,
+    buffer: Vec<u16>,
+}
+
+impl FrameSplitter {
+    /// Creates a new frame splitter which splits frames at pulses longer than `break_pulse_length`
+    /// microseconds, and returns early with a partial frame if `max_length` pulses arrive without
+    /// a break.
+    pub fn new(break_pulse_length: u16, max_length: usize) -> Self {
+        Self {
+            break_pulse_length,
+            max_length,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Adds the next pulse duration in microseconds, returning a complete frame if this pulse
+    /// ended one.
+    ///
+    /// If the buffer reaches the maximum length then its contents are returned as they are, and
+    /// the buffer is reset.
+    pub fn push(&mut self, pulse: u16) -> Option<Vec<u16>> {
+        if pulse > self.break_pulse_length {
+            let mut frame = replace(&mut self.buffer, vec![pulse]);
+            // Don't bother returning a frame with no pulses other than the break.
+            if frame.is_empty() || (frame.len() == 1 && frame[0] > self.break_pulse_length) {
+                return None;
+            }
+            frame.push(pulse);
+            Some(frame)
+        } else {
+            self.buffer.push(pulse);
+            if self.buffer.len() >= self.max_length {
+                Some(take(&mut self.buffer))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode, Code, DEFAULT_BREAK_PULSE_LENGTH};
+
+    #[test]
+    fn split_frames() {
+        let mut splitter = FrameSplitter::new(DEFAULT_BREAK_PULSE_LENGTH, 100);
+        let frames: Vec<Vec<u16>> = [
+            300, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 10000, 10000, 1000, 333,
+            333, 1000, 333, 10000,
+        ]
+        .into_iter()
+        .filter_map(|pulse| splitter.push(pulse))
+        .collect();
+        assert_eq!(
+            frames,
+            vec![
+                vec![300, 10000],
+                vec![10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 10000],
+                vec![10000, 1000, 333, 333, 1000, 333, 10000],
+            ]
+        );
+        assert_eq!(
+            decode(&frames[1]),
+            Ok(Code {
+                value: 0b1101,
+                length: 4
+            })
+        );
+    }
+
+    #[test]
+    fn max_length() {
+        let mut splitter = FrameSplitter::new(DEFAULT_BREAK_PULSE_LENGTH, 3);
+        assert_eq!(splitter.push(10000), None);
+        assert_eq!(splitter.push(1000), None);
+        assert_eq!(splitter.push(333), Some(vec![10000, 1000, 333]));
+        assert_eq!(splitter.push(1000), None);
+        assert_eq!(splitter.push(333), None);
+        assert_eq!(splitter.push(10000), Some(vec![1000, 333, 10000]));
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Decoding of the frame structure used by KeeLoq rolling code remotes.
+
+use crate::{round_div, Code, Error};
+
+/// The number of bits in a KeeLoq frame.
+pub(crate) const FRAME_BITS: usize = 66;
+
+/// The length of the header after the preamble, as a multiple of the basic pulse element.
+pub(crate) const HEADER_MULTIPLE: u64 = 10;
+
+/// The fields of a KeeLoq frame.
+///
+/// The rolling part of the code is left encrypted, but the fixed part can be used to identify
+/// which remote and which buttons sent it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct KeeloqFrame {
+    /// The encrypted rolling part of the code.
+    pub encrypted: u32,
+    /// The 28 bit serial number of the remote.
+    pub serial: u32,
+    /// The 4 button status bits.
+    pub buttons: u8,
+    /// Whether the remote reported that its battery is low.
+    pub low_battery: bool,
+    /// Whether this is a repeat of a frame while the button is held.
+    pub repeat: bool,
+}
+
+impl From<KeeloqFrame> for Code {
+    /// Returns the 66 bits of the frame as a code, with the first bit sent as the least significant
+    /// bit.
+    fn from(frame: KeeloqFrame) -> Self {
+        Code {
+            value: u128::from(frame.encrypted)
+                | u128::from(frame.serial & 0x0fff_ffff) << 32
+                | u128::from(frame.buttons & 0xf) << 60
+                | u128::from(frame.low_battery) << 64
+                | u128::from(frame.repeat) << 65,
+            length: FRAME_BITS as u8,
+        }
+    }
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a KeeLoq frame.
+///
+/// The frame starts after a preamble of short pulses and a header low pulse 10 times as long as the
+/// basic pulse element. Each of the 66 bits is then a high and low pulse with a total length of 3
+/// elements: a short high pulse for a 1 or a long high pulse for a 0. Bits are sent least
+/// significant first.
+pub fn decode_keeloq(pulses: &[u16]) -> Result<KeeloqFrame, Error> {
+    if pulses.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    // Find the header, which is a low pulse following a high pulse of the preamble.
+    let header = (1..pulses.len())
+        .step_by(2)
+        .find(|&i| {
+            let element = u64::from(pulses[i - 1]);
+            element != 0 && round_div(pulses[i].into(), element) == HEADER_MULTIPLE
+        })
+        .ok_or(Error::NoStart)?;
+    let element = round_div(pulses[header].into(), HEADER_MULTIPLE);
+    let symbols = &pulses[header + 1..];
+    if symbols.len() < 2 * FRAME_BITS - 1 {
+        return Err(Error::TooShort);
+    }
+
+    let mut bits: u128 = 0;
+    for i in 0..FRAME_BITS {
+        let high = symbols[2 * i];
+        // The low pulse of the last bit runs into the guard time, so can't be checked.
+        let low = symbols
+            .get(2 * i + 1)
+            .copied()
+            .filter(|_| i < FRAME_BITS - 1);
+        let periods = (
+            round_div(high.into(), element),
+            low.map(|low| round_div(low.into(), element)),
+        );
+        let bit = match periods {
+            (1, Some(2) | None) => true,
+            (2, Some(1) | None) => false,
+            _ => return Err(Error::InvalidPulseLength(high, low.unwrap_or(0))),
+        };
+        bits |= u128::from(bit) << i;
+    }
+
+    Ok(KeeloqFrame {
+        encrypted: bits as u32,
+        serial: (bits >> 32) as u32 & 0x0fff_ffff,
+        buttons: (bits >> 60) as u8 & 0xf,
+        low_battery: bits >> 64 & 1 == 1,
+        repeat: bits >> 65 & 1 == 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes the given bits, least significant first, as a KeeLoq transmission.
+    fn encode(bits: u128, element: u16) -> Vec<u16> {
+        let mut pulses = Vec::new();
+        for _ in 0..11 {
+            pulses.extend([element, element]);
+        }
+        pulses.extend([element, element * 10]);
+        for i in 0..FRAME_BITS {
+            if bits >> i & 1 == 1 {
+                pulses.extend([element, element * 2]);
+            } else {
+                pulses.extend([element * 2, element]);
+            }
+        }
+        *pulses.last_mut().unwrap() = 15000;
+        pulses
+    }
+
+    #[test]
+    fn decode_fields() {
+        let bits = 0x1234_5678 | 0x0abc_def1 << 32 | 0x5 << 60 | 1 << 65;
+        assert_eq!(
+            decode_keeloq(&encode(bits, 400)),
+            Ok(KeeloqFrame {
+                encrypted: 0x1234_5678,
+                serial: 0x0abc_def1,
+                buttons: 0x5,
+                low_battery: false,
+                repeat: true,
+            })
+        );
+        assert_eq!(
+            decode_keeloq(&encode(bits, 400)).map(Code::from),
+            Ok(Code {
+                value: bits,
+                length: 66
+            })
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let pulses = encode(0, 400);
+        assert_eq!(decode_keeloq(&pulses[..100]), Err(Error::TooShort));
+        assert_eq!(decode_keeloq(&pulses[..20]), Err(Error::NoStart));
+    }
+}
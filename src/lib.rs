@@ -3,38 +3,476 @@
 // See LICENSE-APACHE and LICENSE-MIT for details.
 
 //! A library for decoding 433 MHz RF remote codes.
+//!
+//! # Features
+//!
+//! Decoding a single frame with functions such as [`decode`] and [`decode_all_into`] only needs
+//! `core`, so the crate can be used with `#![no_std]` on targets without an operating system by
+//! disabling the default `std` feature. Functions which return a `Vec` or `String` need the `alloc`
+//! feature, and those which need I/O, hash maps or the system clock need `std`.
 
-use std::{
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+mod analyze;
+mod biphase;
+#[cfg(feature = "alloc")]
+mod bytes;
+#[cfg(feature = "std")]
+mod codebook;
+#[cfg(feature = "alloc")]
+mod codegen;
+#[cfg(feature = "std")]
+mod debouncer;
+mod dipswitch;
+#[cfg(feature = "std")]
+mod flipper;
+#[cfg(feature = "alloc")]
+mod frame_splitter;
+mod keeloq;
+mod ppm;
+pub mod prelude;
+#[cfg(feature = "alloc")]
+mod protocol;
+mod pulse_decoder;
+mod rcswitch;
+#[cfg(feature = "alloc")]
+mod receiver;
+#[cfg(any(feature = "serde", feature = "std"))]
+mod rfbridge;
+#[cfg(feature = "samples")]
+mod samples;
+#[cfg(feature = "serde")]
+mod session;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "serde")]
+pub mod with_length;
+
+#[cfg(feature = "serde")]
+use alloc::string::ToString;
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String, sync::Arc, vec, vec::Vec};
+#[cfg(feature = "alloc")]
+pub use analyze::{analyze, Analysis};
+pub use biphase::decode_biphase;
+#[cfg(feature = "alloc")]
+pub use bytes::{pulses_from_bytes, pulses_to_bytes};
+#[cfg(feature = "std")]
+pub use codebook::CodeBook;
+#[cfg(feature = "alloc")]
+pub use codegen::SourceLanguage;
+#[cfg(feature = "alloc")]
+use core::time::Duration;
+use core::{
     fmt::{self, Debug, Formatter},
-    ops::{Add, Div},
+    ops::Range,
 };
+#[cfg(feature = "std")]
+pub use debouncer::Debouncer;
+pub use dipswitch::{TriState, TriStateCode};
+#[cfg(feature = "std")]
+pub use flipper::{pulses_from_sub, FLIPPER_OOK_PRESET};
+#[cfg(feature = "alloc")]
+pub use frame_splitter::FrameSplitter;
+pub use keeloq::{decode_keeloq, KeeloqFrame};
+pub use ppm::decode_ppm;
+#[cfg(feature = "alloc")]
+pub use protocol::{
+    BiphaseProtocol, KeeloqProtocol, PpmProtocol, Protocol, ProtocolRegistry, PwmProtocol,
+};
+pub use pulse_decoder::PulseDecoder;
+pub use rcswitch::{decode_rcswitch, RcSwitchProtocol, RCSWITCH_PROTOCOLS};
+#[cfg(feature = "async")]
+pub use receiver::AsyncPulseReceiver;
+#[cfg(feature = "alloc")]
+pub use receiver::{Clock, EdgeCapture, DEFAULT_MAX_PULSE_LENGTH};
+#[cfg(feature = "samples")]
+pub use samples::pulses_from_samples;
+#[cfg(feature = "serde")]
+pub use session::CaptureSession;
 use thiserror::Error;
 
-const BREAK_PULSE_LENGTH: u16 = 3000;
+/// Logs a trace message if the `log` feature is enabled, and otherwise does nothing.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::trace!($($arg)*);
+    };
+}
+
+/// The default minimum length in microseconds of a pulse for it to be treated as a break between
+/// codes.
+pub const DEFAULT_BREAK_PULSE_LENGTH: u16 = 3000;
+
+/// The short pulse duration in microseconds which [`encode`] uses, typical of PT2262 and EV1527
+/// remotes.
+pub const DEFAULT_SHORT_DURATION: u16 = 320;
+
+/// The length of the sync pulse between encoded codes, as a multiple of the short pulse duration.
+const SYNC_MULTIPLE: u16 = 31;
 
 /// An error decoding an RF button code.
 #[derive(Clone, Debug, Error, Eq, PartialEq)]
 pub enum Error {
+    /// No pulses were given to decode.
+    #[error("No pulses")]
+    EmptyInput,
     /// The start pulse of the code sequence couldn't be found.
     #[error("Couldn't find start pulse")]
     NoStart,
     /// There were not enough pulses to decode the code.
     #[error("Too few pulses")]
     TooShort,
+    /// The pulses at the start of the code were too short to infer the short pulse duration.
+    #[error("No signal found after start pulse")]
+    NoSignal,
     /// A pair of pulses in the code were of an unexpected length.
     #[error("Invalid pulse length ({0} μs high {1} μs low)")]
     InvalidPulseLength(u16, u16),
+    /// The code was decoded but failed its checksum.
+    #[error("Checksum failed")]
+    ChecksumFailed,
+    /// The code had more bits than fit in a [`Code`].
+    #[error("Code longer than 128 bits")]
+    TooLong,
+    /// The value of a code doesn't fit in its length.
+    #[error("Value {0:#x} doesn't fit in {1} bits")]
+    ValueOutOfRange(u128, u8),
+    /// The given protocol number is not known.
+    #[error("Unknown protocol {0}")]
+    UnknownProtocol(usize),
+    /// Frames were decoded, but not enough of them agreed on the same code.
+    #[error("Only {0} frames agreed on the same code")]
+    NoAgreement(usize),
+    /// Pulses couldn't be parsed from bytes because there were an odd number of them.
+    #[error("Odd number of bytes ({0}) for pulses")]
+    OddByteLength(usize),
+    /// The pulses ran out before the break at the end of the frame.
+    #[error("Couldn't find break pulse at end of frame")]
+    NoEnd,
+    /// The frame didn't start with the sync word given in the options.
+    #[error("Frame didn't start with sync word")]
+    SyncMismatch,
+    /// A code or string isn't a valid tri-state code.
+    #[error("Not a valid tri-state code")]
+    NotTriState,
 }
 
 /// A decoded RF button code.
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
 #[derive(Copy, Clone, Eq, Hash, PartialEq)]
 pub struct Code {
     /// The decoded value.
-    pub value: u32,
+    pub value: u128,
     /// The length in bits.
     pub length: u8,
 }
 
+impl Code {
+    /// Creates a new code with the given value and length in bits.
+    ///
+    /// Returns [`Error::TooLong`] if the length is more than 128 bits, or
+    /// [`Error::ValueOutOfRange`] if the value doesn't fit in the length.
+    pub fn new(value: u128, length: u8) -> Result<Self, Error> {
+        if u32::from(length) > u128::BITS {
+            Err(Error::TooLong)
+        } else if value.checked_shr(length.into()).unwrap_or(0) != 0 {
+            Err(Error::ValueOutOfRange(value, length))
+        } else {
+            Ok(Self { value, length })
+        }
+    }
+
+    /// Returns whether the two codes have the same value, ignoring their lengths.
+    ///
+    /// This is useful for matching codes which were captured with a different number of leading
+    /// zero bits. Unlike `==`, `Code { value: 0x123, length: 12 }` and
+    /// `Code { value: 0x123, length: 16 }` are considered equal.
+    pub fn value_eq(&self, other: &Code) -> bool {
+        self.value == other.value
+    }
+
+    /// Returns the code with any leading 0 bits removed, so its length is the minimum needed for
+    /// its value.
+    ///
+    /// This is useful for comparing codes which were captured with a different number of leading
+    /// zero bits. Note though that for most protocols the length is a fixed part of the code, so
+    /// two codes which differ only in leading zeros may still be from different devices.
+    pub fn trimmed(&self) -> Code {
+        Code {
+            value: self.value,
+            length: (u128::BITS - self.value.leading_zeros()) as u8,
+        }
+    }
+
+    /// Returns the code with the given length, adding or removing leading 0 bits as needed.
+    ///
+    /// Returns [`Error::TooLong`] if the length is more than 128 bits, or
+    /// [`Error::ValueOutOfRange`] if the value doesn't fit in the length.
+    pub fn padded_to(&self, length: u8) -> Result<Code, Error> {
+        Self::new(self.value, length)
+    }
+
+    /// Returns the code with the given length, keeping only the bits of the value which fit in it.
+    ///
+    /// Unlike [`padded_to`](Self::padded_to), bits above the new length are dropped when
+    /// shrinking, so a 24 bit `0xffffff` becomes a 20 bit `0x0fffff`. Leading 0 bits are added
+    /// when growing.
+    ///
+    /// Returns [`Error::TooLong`] if the length is more than 128 bits.
+    pub fn truncated_to(&self, length: u8) -> Result<Code, Error> {
+        if u32::from(length) > u128::BITS {
+            return Err(Error::TooLong);
+        }
+        let mask = 1u128
+            .checked_shl(length.into())
+            .unwrap_or(0)
+            .wrapping_sub(1);
+        Ok(Code {
+            value: self.value & mask,
+            length,
+        })
+    }
+
+    /// Converts the value of the code from Gray code to binary, keeping the same length.
+    ///
+    /// This is for devices such as rotary dials which encode a position in Gray code, so that
+    /// adjacent positions differ by a single bit. To convert just one field of a code, make a code
+    /// from the field first, such as `Code::new(code.field(4, 3), 3)?.from_gray()`.
+    pub fn from_gray(&self) -> Code {
+        let mut value = self.value;
+        let mut shift = 1;
+        while shift < u128::BITS {
+            value ^= value >> shift;
+            shift *= 2;
+        }
+        Code {
+            value,
+            length: self.length,
+        }
+    }
+
+    /// Returns the code with the order of its bits reversed, keeping the same length.
+    ///
+    /// This converts between a value read most significant bit first and one read least
+    /// significant bit first, such as for a code decoded with [`BitOrder::LsbFirst`] which needs to
+    /// be transmitted again with [`to_pulses`](Self::to_pulses).
+    pub fn reversed(&self) -> Code {
+        Code {
+            value: self
+                .value
+                .reverse_bits()
+                .checked_shr(u128::BITS - u32::from(self.length))
+                .unwrap_or(0),
+            length: self.length,
+        }
+    }
+
+    /// Returns the number of bits which differ between the two codes, or `None` if they have
+    /// different lengths.
+    pub fn hamming_distance(&self, other: &Code) -> Option<u32> {
+        if self.length == other.length {
+            Some((self.value ^ other.value).count_ones())
+        } else {
+            None
+        }
+    }
+
+    /// Creates a code from the given bits, starting from the most significant.
+    ///
+    /// Returns [`Error::TooLong`] if there are more than 128 bits.
+    pub fn from_bits(bits: &[bool]) -> Result<Self, Error> {
+        if bits.len() > u128::BITS as usize {
+            return Err(Error::TooLong);
+        }
+        Ok(Self {
+            value: bits
+                .iter()
+                .fold(0, |value, &bit| value << 1 | u128::from(bit)),
+            length: bits.len() as u8,
+        })
+    }
+
+    /// Returns the bits of the code, starting from the most significant.
+    #[cfg(feature = "alloc")]
+    pub fn bits(&self) -> Vec<bool> {
+        (0..self.length)
+            .rev()
+            .map(|bit| self.value.checked_shr(bit.into()).unwrap_or(0) & 1 == 1)
+            .collect()
+    }
+
+    /// Returns the `width` bits of the value starting `offset` bits from the least significant bit.
+    ///
+    /// This is useful for codes made up of several fields, such as a device ID and a button number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset + width` is greater than the length of the code.
+    pub fn field(&self, offset: u8, width: u8) -> u128 {
+        assert!(
+            u16::from(offset) + u16::from(width) <= u16::from(self.length),
+            "Field of {} bits at offset {} is outside {} bit code",
+            width,
+            offset,
+            self.length
+        );
+        let mask = 1u128.checked_shl(width.into()).unwrap_or(0).wrapping_sub(1);
+        self.value.checked_shr(offset.into()).unwrap_or(0) & mask
+    }
+
+    /// Formats the value of the code as a hex string, zero-padded to at least `min_width` digits.
+    ///
+    /// The string is always padded to at least enough digits for the length of the code, so
+    /// `min_width` can only make it longer. For example, a 24 bit code with value `0x48b2a4` is
+    /// formatted as `"048B2A4"` with `uppercase` set and a `min_width` of 7.
+    ///
+    /// The value is formatted as it is, so for a code decoded with [`BitOrder::LsbFirst`] the
+    /// first bit received is the last bit of the string. Use [`reversed`](Self::reversed) first to
+    /// format it in the order it was received.
+    #[cfg(feature = "alloc")]
+    pub fn to_hex_string(&self, uppercase: bool, min_width: usize) -> String {
+        let width = min_width.max(usize::from(self.length).div_ceil(4));
+        if uppercase {
+            format!("{:01$X}", self.value, width)
+        } else {
+            format!("{:01$x}", self.value, width)
+        }
+    }
+
+    /// Makes a best-effort guess at which family of protocol the code came from, based on its
+    /// length and structure.
+    ///
+    /// The heuristics are:
+    /// - 12 bits is [`ProtocolFamily::Pt2262`], as sent by PT2262 and HT12E encoders with one bit
+    ///   per address or data pin.
+    /// - 24 bits where every pair of bits is `00`, `11` or `01` is also
+    ///   [`ProtocolFamily::Pt2262`], with each pair representing a tri-state pin which is low, high
+    ///   or floating.
+    /// - Any other 24 bits is [`ProtocolFamily::Ev1527`], with a 20 bit ID and 4 data bits.
+    /// - 28 bits is [`ProtocolFamily::Ht6p20b`].
+    /// - 32 bits is [`ProtocolFamily::Keeloq`], assuming it is the encrypted part of a KeeLoq frame
+    ///   which was cut short.
+    ///
+    /// Many other encoders share these lengths, so this is only advisory.
+    pub fn guess_protocol(&self) -> Option<ProtocolFamily> {
+        match self.length {
+            12 => Some(ProtocolFamily::Pt2262),
+            24 if (0..12).all(|pair| (self.value >> (2 * pair)) & 0b11 != 0b10) => {
+                Some(ProtocolFamily::Pt2262)
+            }
+            24 => Some(ProtocolFamily::Ev1527),
+            28 => Some(ProtocolFamily::Ht6p20b),
+            32 => Some(ProtocolFamily::Keeloq),
+            _ => None,
+        }
+    }
+
+    /// Encodes the code as a sequence of pulse durations in microseconds (starting with a high
+    /// pulse), using the given short pulse duration.
+    ///
+    /// This is a single frame in the format expected by [`decode`]: a sync break, then each bit
+    /// from the most significant, then another sync break. The sync breaks are 31 times the short
+    /// pulse duration.
+    #[cfg(feature = "alloc")]
+    pub fn to_pulses(&self, short_duration: u16) -> Vec<u16> {
+        self.repeat_pulses(
+            short_duration,
+            1,
+            short_duration.saturating_mul(SYNC_MULTIPLE),
+        )
+    }
+
+    /// Returns the total duration in microseconds of the pulses returned by
+    /// [`to_pulses`](Self::to_pulses) for the given short pulse duration.
+    pub fn duration_micros(&self, short_duration: u16) -> u32 {
+        let short = u32::from(short_duration);
+        let long = u32::from(short_duration.saturating_mul(3));
+        let sync = u32::from(short_duration.saturating_mul(SYNC_MULTIPLE));
+        2 * (short + sync) + u32::from(self.length) * (short + long)
+    }
+
+    /// Encodes the code as a full transmission of `repeats` frames, each preceded and followed by
+    /// a break of `gap` microseconds.
+    ///
+    /// The returned pulse durations are in microseconds, starting with a high pulse.
+    #[cfg(feature = "alloc")]
+    pub fn repeat_pulses(&self, short_duration: u16, repeats: usize, gap: u16) -> Vec<u16> {
+        let options = DecodeOptions::default();
+        self.pwm_pulses(short_duration, options.one, options.zero, repeats, gap)
+    }
+
+    /// Encodes the code as `repeats` frames, each preceded and followed by a break of `gap`
+    /// microseconds, with the given high and low pulse length multiples for each 1 and 0 bit.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn pwm_pulses(
+        &self,
+        short_duration: u16,
+        one: (u8, u8),
+        zero: (u8, u8),
+        repeats: usize,
+        gap: u16,
+    ) -> Vec<u16> {
+        let pulse = |multiple: u8| short_duration.saturating_mul(multiple.into());
+        let mut pulses = Vec::with_capacity(2 + repeats * (2 * usize::from(self.length) + 2));
+        pulses.extend([short_duration, gap]);
+        for _ in 0..repeats {
+            for bit in (0..self.length).rev() {
+                let (high, low) = if self.value.checked_shr(bit.into()).unwrap_or(0) & 1 == 1 {
+                    one
+                } else {
+                    zero
+                };
+                pulses.extend([pulse(high), pulse(low)]);
+            }
+            pulses.extend([short_duration, gap]);
+        }
+        pulses
+    }
+}
+
+/// A family of encoder chips or protocols which a code may have come from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProtocolFamily {
+    /// PT2262, HT12E and compatible encoders with fixed address and data pins.
+    Pt2262,
+    /// EV1527 and compatible encoders with a 20 bit ID programmed at the factory.
+    Ev1527,
+    /// HT6P20B encoders.
+    Ht6p20b,
+    /// KeeLoq rolling code encoders.
+    Keeloq,
+}
+
+impl From<Code> for u128 {
+    fn from(code: Code) -> Self {
+        code.value
+    }
+}
+
+impl TryFrom<(u128, u8)> for Code {
+    type Error = Error;
+
+    /// Tries to create a code from a value and length in bits, as for [`Code::new`].
+    fn try_from((value, length): (u128, u8)) -> Result<Self, Error> {
+        Self::new(value, length)
+    }
+}
+
+impl TryFrom<&[u16]> for Code {
+    type Error = Error;
+
+    /// Tries to decode a button code from the given sequence of pulse durations in microseconds, as
+    /// for [`decode`].
+    fn try_from(pulses: &[u16]) -> Result<Self, Error> {
+        decode(pulses)
+    }
+}
+
 impl Debug for Code {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
@@ -45,21 +483,89 @@ impl Debug for Code {
     }
 }
 
+/// Formats the value in binary, zero-padded to the length of the code.
+impl fmt::Binary for Code {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        pad_digits(f, self.value, self.length.into(), 1, b"01", "0b")
+    }
+}
+
+/// Formats the value in lowercase hex, zero-padded to enough digits for the length of the code.
+impl fmt::LowerHex for Code {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let width = usize::from(self.length).div_ceil(4);
+        pad_digits(f, self.value, width, 4, b"0123456789abcdef", "0x")
+    }
+}
+
+/// Formats the value in uppercase hex, zero-padded to enough digits for the length of the code.
+impl fmt::UpperHex for Code {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let width = usize::from(self.length).div_ceil(4);
+        pad_digits(f, self.value, width, 4, b"0123456789ABCDEF", "0x")
+    }
+}
+
+/// Writes `value` with the given number of bits per digit, zero-padded to at least `width` digits,
+/// then pads the result according to the formatter's flags.
+fn pad_digits(
+    f: &mut Formatter,
+    value: u128,
+    width: usize,
+    bits_per_digit: u32,
+    digits: &[u8],
+    prefix: &str,
+) -> fmt::Result {
+    let mut buffer = [0; 128];
+    let needed = (u128::BITS - value.leading_zeros()).div_ceil(bits_per_digit) as usize;
+    let count = needed.max(width).max(1);
+    let mask = (1 << bits_per_digit) - 1;
+    for (i, digit) in buffer[..count].iter_mut().rev().enumerate() {
+        *digit =
+            digits[(value.checked_shr(i as u32 * bits_per_digit).unwrap_or(0) & mask) as usize];
+    }
+    let digits = core::str::from_utf8(&buffer[..count]).map_err(|_| fmt::Error)?;
+    f.pad_integral(true, prefix, digits)
+}
+
 #[cfg(feature = "serde")]
 impl<'de> serde::Deserialize<'de> for Code {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
+        if !deserializer.is_human_readable() {
+            let (ValueBytes(bytes), length) = <(ValueBytes, u8)>::deserialize(deserializer)?;
+            if bytes.len() > 16 {
+                return Err(serde::de::Error::invalid_length(
+                    bytes.len(),
+                    &"no more than 16 bytes",
+                ));
+            }
+            let value = bytes
+                .iter()
+                .fold(0, |value, &byte| value << 8 | u128::from(byte));
+            return Code::new(value, length).map_err(serde::de::Error::custom);
+        }
         let s = String::deserialize(deserializer)?;
-        if s.len() > 8 {
+        // Codes with a length which isn't a multiple of 4, or which are empty, are serialized with
+        // an explicit length.
+        if s.contains("bit:") {
+            return with_length::parse(&s).ok_or_else(|| {
+                serde::de::Error::invalid_value(
+                    serde::de::Unexpected::Str(&s),
+                    &"a code like \"19bit:0x4763a\" with a value which fits in the length",
+                )
+            });
+        }
+        if s.len() > 32 {
             return Err(serde::de::Error::invalid_value(
                 serde::de::Unexpected::Str(&s),
-                &"no more than 8 characters",
+                &"no more than 32 characters",
             ));
         }
         let value =
-            u32::from_str_radix(&s, 16).map_err(|e| serde::de::Error::custom(e.to_string()))?;
+            u128::from_str_radix(&s, 16).map_err(|e| serde::de::Error::custom(e.to_string()))?;
         Ok(Self {
             value,
             length: s.len() as u8 * 4,
@@ -73,164 +579,4329 @@ impl serde::Serialize for Code {
     where
         S: serde::Serializer,
     {
-        if self.length % 4 != 0 {
-            return Err(serde::ser::Error::custom(
-                "Only codes with length a multiple of 4 can be serialized.",
-            ));
+        // Binary formats get the value as big-endian bytes and the length, which is much more
+        // compact.
+        if !serializer.is_human_readable() {
+            let bytes = self.value.to_be_bytes();
+            let byte_length = usize::from(self.length).div_ceil(8).min(bytes.len());
+            return (
+                ValueBytes(bytes[bytes.len() - byte_length..].to_vec()),
+                self.length,
+            )
+                .serialize(serializer);
+        }
+        // An empty code would otherwise be serialized as "0", which is read back as 4 bits.
+        if self.length == 0 || !self.length.is_multiple_of(4) {
+            return with_length::serialize(self, serializer);
         }
         let s = format!("{:01$x}", self.value, usize::from(self.length) / 4);
         serializer.serialize_str(&s)
     }
 }
 
+/// The value of a code as big-endian bytes, serialized as a byte array rather than a sequence of
+/// integers.
+#[cfg(feature = "serde")]
+struct ValueBytes(Vec<u8>);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ValueBytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ValueBytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct ValueBytesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ValueBytesVisitor {
+            type Value = ValueBytes;
+
+            fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+                formatter.write_str("a byte array")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(ValueBytes(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(ValueBytes(v))
+            }
+
+            // Some formats can't tell byte arrays apart from sequences of integers.
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut bytes = Vec::new();
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(ValueBytes(bytes))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(ValueBytesVisitor)
+    }
+}
+
+/// Options to control how button codes are decoded.
+///
+/// The defaults match [`decode`]. For a noisy receiver or an unusual remote, the break pulse
+/// length, the ratios of each symbol and how far pulses may be from them can all be changed, and
+/// the options passed to [`decode_with`]:
+///
+/// ```
+/// use rfbutton::{decode_with, Code, DecodeOptions};
+///
+/// let options = DecodeOptions {
+///     one: (2, 1),
+///     zero: (1, 2),
+///     break_pulse_length: 3000,
+///     tolerance: Some(30),
+///     ..Default::default()
+/// };
+/// let pulses = [400, 4000, 900, 350, 420, 850, 780, 450, 400, 4000];
+/// assert_eq!(
+///     decode_with(&pulses, &options),
+///     Ok(Code {
+///         value: 0b101,
+///         length: 3
+///     })
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DecodeOptions {
+    /// The high and low pulse lengths of a 1 bit, as multiples of the short pulse duration.
+    pub one: (u8, u8),
+    /// The high and low pulse lengths of a 0 bit, as multiples of the short pulse duration.
+    pub zero: (u8, u8),
+    /// The minimum length in microseconds of a pulse for it to be treated as a break between codes.
+    pub break_pulse_length: u16,
+    /// If set, a pulse within a frame only ends it if it is longer than this multiple of the short
+    /// pulse duration, rather than longer than `break_pulse_length`.
+    ///
+    /// This allows slow protocols whose long data pulses are longer than the break pulse length to
+    /// be decoded. `break_pulse_length` is still used to find the start of each frame.
+    pub break_multiple: Option<u16>,
+    /// A checksum to verify decoded codes against, if the protocol includes one.
+    pub checksum: Option<Checksum>,
+    /// The minimum number of bits for a frame to be accepted.
+    ///
+    /// Shorter frames, such as a partial frame at the start of a capture, are skipped and the next
+    /// frame is tried instead. If this is set then frames which fail to decode are skipped too, as
+    /// a partial frame may start partway through a symbol.
+    pub min_bits: u8,
+    /// The exact number of bits expected in a frame, if known.
+    ///
+    /// If set, decoding stops after this many bits even if more valid symbols follow, and frames
+    /// with fewer bits are treated as too short in the same way as for `min_bits`.
+    pub expected_length: Option<u8>,
+    /// How to detect the start of a frame.
+    pub start: Start,
+    /// Whether to check that the first two symbols after a possible start are valid before
+    /// accepting it, and otherwise to keep looking for the next start.
+    ///
+    /// This avoids false starts from noise which happens to look like a break.
+    pub validate_start: bool,
+    /// Whether to treat the beginning of the pulses as the start of a frame, unless they start with
+    /// a break or sync pair.
+    ///
+    /// This allows frames which have already been split from a capture without their leading
+    /// break, such as by [`FrameSplitter`] when a frame reaches its maximum length, to be decoded.
+    pub assume_start: bool,
+    /// The level of the first pulse.
+    ///
+    /// This only matters when the start of a frame depends on the levels of the pulses, such as
+    /// with [`Start::HighBreak`], [`Start::SyncPair`] or `assume_start`. With [`Start::Break`] each
+    /// frame starts after its break pulse whatever its level, so captures which start with a low
+    /// pulse are decoded the same either way.
+    pub polarity: Polarity,
+    /// Whether to reject frames which don't end with a break, because the pulses ran out first.
+    ///
+    /// This avoids false decodes from noise at the end of a capture. Frames which end after
+    /// `expected_length` bits are still accepted.
+    pub require_trailing_break: bool,
+    /// The number of pulses to skip after the start of a frame before pairing pulses into symbols.
+    ///
+    /// Set this to 1 for protocols which put the low half of each symbol first, so that a 1 is a
+    /// long low pulse followed by a short high pulse.
+    pub pair_offset: usize,
+    /// The number of preamble bits at the start of each frame, which are skipped rather than
+    /// included in the code.
+    ///
+    /// `min_bits` and `expected_length` don't include the preamble bits.
+    pub preamble_bits: u8,
+    /// A fixed header which each frame must start with, as a value and a number of bits.
+    ///
+    /// If set, frames which don't start with the header fail with [`Error::SyncMismatch`], and the
+    /// header is removed from the decoded code. `min_bits`, `expected_length` and `checksum` only
+    /// apply to the bits after the header, and the positions of guessed bits count from the first
+    /// bit after it.
+    pub sync_word: Option<(u32, u8)>,
+    /// The short pulse duration in microseconds, if it is already known for the device.
+    ///
+    /// By default it is estimated from the pulses of each frame, so a frame with many noisy pulses
+    /// may fail to decode.
+    pub short_duration: Option<u16>,
+    /// Whether to estimate separate short pulse durations for high and low pulses, for receivers
+    /// which consistently read one longer than the other.
+    ///
+    /// Each is estimated from the corresponding pulses of the first two symbols of each frame. This
+    /// has no effect if `short_duration` is set.
+    pub separate_high_low: bool,
+    /// If set, each pair of pulses is treated as whichever of a 1 or a 0 it is closest to, as long
+    /// as the total difference of its pulses from the ideal durations is within this percentage of
+    /// the ideal duration of the symbol.
+    ///
+    /// By default each pulse must instead round to exactly the right multiple of the short pulse
+    /// duration.
+    pub tolerance: Option<u8>,
+    /// If set, the tolerance to use instead of `tolerance` for the first `startup_symbols` symbols
+    /// of each frame, as a percentage in the same way.
+    ///
+    /// This is for receivers whose automatic gain control is still settling at the start of a
+    /// frame, so the first pulses are noisier than the rest.
+    pub startup_tolerance: Option<u8>,
+    /// The number of symbols at the start of each frame to decode with `startup_tolerance`,
+    /// including any preamble and sync word.
+    pub startup_symbols: u8,
+    /// What to do with an ambiguous symbol which is about 2 short pulse durations high and 2 low.
+    pub ambiguity: AmbiguityPolicy,
+    /// The order in which the bits of each frame are sent, for devices which send the least
+    /// significant bit first.
+    ///
+    /// The sync word is still matched in the order it is received, and the positions of guessed
+    /// bits still count from the first bit received.
+    pub bit_order: BitOrder,
+    /// Whether to guess a bit for a pair of pulses which isn't a valid symbol and carry on decoding
+    /// from the next pair, rather than failing.
+    ///
+    /// Use [`decode_with_uncertainty`] to find out which bits were guessed.
+    pub resync_on_error: bool,
+    /// The bit to guess for an invalid symbol when `resync_on_error` is set.
+    pub resync_placeholder: bool,
+    /// Pulses shorter than this many microseconds are treated as glitches by [`recognize`], and
+    /// merged with the pulses either side of them.
+    pub glitch_length: u16,
+    /// The minimum number of frames which must agree on a code for [`recognize`] to accept it.
+    pub min_agreement: usize,
+    /// A callback to observe how each pulse is treated while decoding, such as for a tool to tune
+    /// the other options interactively.
+    #[cfg(feature = "alloc")]
+    pub on_pulse: Option<PulseObserver>,
+}
+
+impl DecodeOptions {
+    /// Returns the minimum number of bits for a frame to be accepted, taking into account both
+    /// `min_bits` and `expected_length`.
+    fn required_bits(&self) -> u8 {
+        self.min_bits.max(self.expected_length.unwrap_or(0))
+    }
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self {
+            one: (3, 1),
+            zero: (1, 3),
+            break_pulse_length: DEFAULT_BREAK_PULSE_LENGTH,
+            break_multiple: None,
+            checksum: None,
+            min_bits: 0,
+            expected_length: None,
+            start: Start::Break,
+            validate_start: false,
+            assume_start: false,
+            polarity: Polarity::Normal,
+            require_trailing_break: false,
+            pair_offset: 0,
+            preamble_bits: 0,
+            sync_word: None,
+            short_duration: None,
+            separate_high_low: false,
+            tolerance: None,
+            startup_tolerance: None,
+            startup_symbols: 0,
+            ambiguity: AmbiguityPolicy::Abort,
+            bit_order: BitOrder::MsbFirst,
+            resync_on_error: false,
+            resync_placeholder: false,
+            glitch_length: 0,
+            min_agreement: 1,
+            #[cfg(feature = "alloc")]
+            on_pulse: None,
+        }
+    }
+}
+
+/// A method of detecting the start of a frame.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Start {
+    /// A single pulse longer than the break pulse length, after which the frame starts.
+    Break,
+    /// A high pulse longer than the break pulse length, such as a long preamble, followed by a low
+    /// pulse of any length, after which the frame starts.
+    ///
+    /// The high pulse must be at an even index, or an odd index if `DecodeOptions::polarity` is
+    /// [`Polarity::Inverted`].
+    HighBreak,
+    /// A high pulse followed by a low pulse with the given lengths as multiples of the short pulse
+    /// duration, such as `SyncPair(1, 31)`, after which the frame starts.
+    ///
+    /// The high pulse must be at an even index, or an odd index if `DecodeOptions::polarity` is
+    /// [`Polarity::Inverted`].
+    SyncPair(u8, u8),
+}
+
+/// The order in which the bits of a code are sent.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BitOrder {
+    /// The first bit sent is the most significant bit of the value.
+    MsbFirst,
+    /// The first bit sent is the least significant bit of the value.
+    LsbFirst,
+}
+
+/// What to do with an ambiguous symbol, which is neither a clean 1 nor a clean 0.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AmbiguityPolicy {
+    /// Fail to decode the frame, in the same way as for any other invalid symbol.
+    Abort,
+    /// Ignore the symbol, so the code has one bit fewer.
+    Skip,
+    /// Guess whichever bit has been decoded more often so far in the frame, or 0 if there is a tie.
+    ///
+    /// The bit is reported as uncertain by [`decode_with_uncertainty`].
+    Majority,
+}
+
+/// A checksum scheme which a decoded code can be verified against.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Checksum {
+    /// The whole code including the parity bit must have an even number of 1 bits.
+    EvenParity,
+    /// The whole code including the parity bit must have an odd number of 1 bits.
+    OddParity,
+}
+
+impl Checksum {
+    /// Returns whether the given code passes the checksum.
+    pub fn verify(self, code: &Code) -> bool {
+        match self {
+            Self::EvenParity => code.value.count_ones().is_multiple_of(2),
+            Self::OddParity => !code.value.count_ones().is_multiple_of(2),
+        }
+    }
+}
+
 /// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
 /// a button code.
 pub fn decode(pulses: &[u16]) -> Result<Code, Error> {
-    // Look for a long low pulse to find the start.
-    let start = pulses
-        .iter()
-        .position(|pulse| *pulse > BREAK_PULSE_LENGTH)
-        .ok_or(Error::NoStart)?
-        + 1;
-    let pulses = &pulses[start..];
+    decode_with(pulses, &DecodeOptions::default())
+}
 
-    if pulses.len() < 4 {
-        return Err(Error::TooShort);
+/// Encodes a button code as a sequence of pulse durations in microseconds (starting with a high
+/// pulse), which [`decode`] will decode back to the same code.
+///
+/// This is a single frame with a sync break before and after it, using the
+/// [`DEFAULT_SHORT_DURATION`]. Use [`Code::to_pulses`] for a different short pulse duration, or
+/// [`Code::repeat_pulses`] to repeat the frame as remotes usually do.
+#[cfg(feature = "alloc")]
+pub fn encode(code: &Code) -> Vec<u16> {
+    code.to_pulses(DEFAULT_SHORT_DURATION)
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a button code using the given options.
+pub fn decode_with(pulses: &[u16], options: &DecodeOptions) -> Result<Code, Error> {
+    decode_scaled(pulses, options, 1, &mut ()).map(|(_, frame)| frame.code)
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a button code from the first frame, and return it along with the index from which to decode the
+/// next frame.
+///
+/// The break at the end of a frame is also the start of the next one, so the index is before it
+/// rather than after it. Passing `&pulses[next..]` to `decode_frame` again decodes the next frame,
+/// in the same way as [`decode_all`] but skipping any frames which fail to decode.
+pub fn decode_frame(pulses: &[u16]) -> Result<(Code, usize), Error> {
+    decode_scaled(pulses, &DecodeOptions::default(), 1, &mut ()).map(|(range, frame)| {
+        let start = range.end - frame.length;
+        (frame.code, start + frame.next)
+    })
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a button code using the given options, and return it along with the positions of any bits which
+/// were guessed.
+///
+/// Bits are only guessed if `options.resync_on_error` is set or `options.ambiguity` is
+/// [`AmbiguityPolicy::Majority`]. Their positions count from 0 for the first (most significant) bit
+/// received.
+#[cfg(feature = "alloc")]
+pub fn decode_with_uncertainty(
+    pulses: &[u16],
+    options: &DecodeOptions,
+) -> Result<(Code, Vec<u8>), Error> {
+    decode_scaled(pulses, options, 1, &mut ())
+        .map(|(_, frame)| (frame.code, uncertain_positions(frame.uncertain)))
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a button code using the given options, and return it along with details of how it was decoded.
+#[cfg(feature = "alloc")]
+pub fn decode_detailed(pulses: &[u16], options: &DecodeOptions) -> Result<DecodeDetails, Error> {
+    decode_scaled(pulses, options, 1, &mut ()).map(|(range, frame)| DecodeDetails {
+        code: frame.code,
+        uncertain: uncertain_positions(frame.uncertain),
+        end: frame.end,
+        pulses: pulses[range].to_vec(),
+        short_duration: to_micros(frame.short_duration, 1),
+    })
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a button code using the given options, and return diagnostics about the attempt whether or not
+/// it succeeds.
+///
+/// If frames are skipped for being too short then the diagnostics are for the last frame which was
+/// tried.
+#[cfg(feature = "alloc")]
+pub fn decode_verbose(
+    pulses: &[u16],
+    options: &DecodeOptions,
+) -> (Result<Code, Error>, Diagnostics) {
+    let mut diagnostics = Diagnostics::default();
+    let result = decode_scaled(pulses, options, 1, &mut diagnostics).map(|(_, frame)| frame.code);
+    if let (Ok(_), Some(start)) = (&result, diagnostics.start) {
+        let end = start + 2 * diagnostics.symbols.len();
+        diagnostics.quality = frame_quality(&pulses[start..end], options);
     }
+    (result, diagnostics)
+}
 
-    // Use the first 4 pulses to calculate the short pulse duration.
-    let short_duration = pulses[0..4].iter().sum::<u16>() / 8;
+/// The level of the first pulse of a capture, given by `DecodeOptions::polarity` or found by
+/// [`decode_auto_polarity`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Polarity {
+    /// The first pulse is high, as the decoding functions expect by default.
+    Normal,
+    /// The first pulse is low, because the receiver inverts its output or the capture started on a
+    /// falling edge.
+    Inverted,
+}
 
-    let mut value = 0;
-    let mut length = 0;
-    let mut pulses = pulses.iter();
-    while let (Some(&high), Some(&low)) = (pulses.next(), pulses.next()) {
-        let high_period = round_div(high, short_duration);
-        let low_period = round_div(low, short_duration);
-        if high_period == 3 && low_period == 1 {
-            value = value << 1 | 1;
-            length += 1;
-        } else if high_period == 1 && low_period == 3 {
-            value <<= 1;
-            length += 1;
-        } else if high > BREAK_PULSE_LENGTH || low > BREAK_PULSE_LENGTH {
-            break;
-        } else {
-            return Err(Error::InvalidPulseLength(high, low));
+impl Polarity {
+    /// Returns the index of the first high pulse at or after `index`.
+    fn next_high(self, index: usize) -> usize {
+        match self {
+            Self::Normal => index.next_multiple_of(2),
+            Self::Inverted => (index + 1).next_multiple_of(2) - 1,
         }
     }
+}
 
-    Ok(Code { value, length })
+/// Given a sequence of pulse durations in microseconds, try to decode a button code both with the
+/// first pulse high and with it low, and return the better result along with which polarity it
+/// came from.
+///
+/// The polarity only makes a difference when the start of a frame depends on the levels of the
+/// pulses, such as with [`Start::HighBreak`], [`Start::SyncPair`] or `options.assume_start`.
+///
+/// If both polarities decode, the one whose frame is closer to multiples of the short pulse
+/// duration is returned, or the normal polarity if they are equally good. If neither decodes then
+/// the error from the normal polarity is returned.
+#[cfg(feature = "alloc")]
+pub fn decode_auto_polarity(
+    pulses: &[u16],
+    options: &DecodeOptions,
+) -> Result<(Code, Polarity), Error> {
+    let (normal, normal_diagnostics) = decode_verbose(pulses, options);
+    let Some(inverted_pulses) = pulses.get(1..) else {
+        return normal.map(|code| (code, Polarity::Normal));
+    };
+    // Skipping the first pulse makes the low pulses start at an even index, like high pulses.
+    let (inverted, inverted_diagnostics) = decode_verbose(inverted_pulses, options);
+    match (normal, inverted) {
+        (Ok(_), Ok(code)) if inverted_diagnostics.quality > normal_diagnostics.quality => {
+            Ok((code, Polarity::Inverted))
+        }
+        (Ok(code), _) => Ok((code, Polarity::Normal)),
+        (Err(_), Ok(code)) => Ok((code, Polarity::Inverted)),
+        (Err(e), Err(_)) => Err(e),
+    }
 }
 
-/// Divide one integer by another, rounding towards the closest integer.
-fn round_div<T: Add<Output = T> + Div<Output = T> + From<u8> + Copy>(dividend: T, divisor: T) -> T {
-    (dividend + divisor / 2.into()) / divisor
+/// Diagnostics about an attempt to decode a frame, returned by [`decode_verbose`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diagnostics {
+    /// The index of the first pulse of the frame after its break or sync pair, if one was found.
+    pub start: Option<usize>,
+    /// The short pulse duration in microseconds used for the frame, if there were enough pulses to
+    /// infer it.
+    pub short_duration: Option<u16>,
+    /// Each pair of pulses which was decoded as a symbol or failed to be, in order, not including
+    /// any break which ended the frame.
+    pub symbols: Vec<SymbolDiagnostics>,
+    /// How the frame ended, if all its symbols could be decoded.
+    pub end: Option<FrameEnd>,
+    /// How cleanly the frame was received from 0 to 1, as for [`quality_trend`], or 0 if it failed
+    /// to decode.
+    pub quality: f32,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Diagnostics about a single symbol of a frame.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SymbolDiagnostics {
+    /// The duration of the high pulse in microseconds.
+    pub high: u16,
+    /// The duration of the low pulse in microseconds.
+    pub low: u16,
+    /// The bit which the pulses represent, or `None` if they aren't a valid symbol.
+    pub bit: Option<bool>,
+    /// The total difference in microseconds of the pulses from the ideal durations for `bit`, or
+    /// for whichever bit they are closer to if it is `None`.
+    pub error: u16,
+}
 
-    #[test]
-    fn decode_no_start() {
-        assert_eq!(decode(&[]), Err(Error::NoStart));
+/// Somewhere to record details of how a frame is decoded, such as [`Diagnostics`].
+///
+/// This is implemented by `()` to record nothing, so that decoding doesn't need an allocator.
+trait RecordDiagnostics {
+    /// Records that a frame starts at the given index, replacing anything recorded about a
+    /// previous frame.
+    fn frame_start(&mut self, _start: usize) {}
+
+    /// Records the short pulse duration in microseconds used for the frame.
+    fn short_duration(&mut self, _short_duration: u16) {}
+
+    /// Records the next symbol of the frame, which is only computed if it is needed.
+    fn symbol(&mut self, _symbol: impl FnOnce() -> SymbolDiagnostics) {}
+
+    /// Records how the frame ended.
+    fn frame_end(&mut self, _end: FrameEnd) {}
+}
+
+impl RecordDiagnostics for () {}
+
+#[cfg(feature = "alloc")]
+impl RecordDiagnostics for Diagnostics {
+    fn frame_start(&mut self, start: usize) {
+        *self = Diagnostics {
+            start: Some(start),
+            ..Default::default()
+        };
     }
 
-    #[test]
-    fn decode_short() {
-        assert_eq!(
-            decode(&[300, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333]),
-            Ok(Code {
-                value: 0b1101,
-                length: 4
-            })
-        );
+    fn short_duration(&mut self, short_duration: u16) {
+        self.short_duration = Some(short_duration);
     }
 
-    #[test]
-    fn decode_short_repeated() {
-        assert_eq!(
-            decode(&[
-                300, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 10000, 1000, 333
-            ]),
-            Ok(Code {
-                value: 0b1101,
-                length: 4
-            })
-        );
+    fn symbol(&mut self, symbol: impl FnOnce() -> SymbolDiagnostics) {
+        self.symbols.push(symbol());
     }
 
-    #[test]
-    fn decode_full() {
-        let decoded = decode(&[
-            320, 10060, 320, 960, 960, 300, 300, 960, 320, 960, 960, 300, 300, 960, 300, 980, 300,
-            960, 960, 300, 320, 960, 960, 300, 960, 320, 300, 960, 300, 960, 960, 320, 300, 960,
-            960, 320, 300, 960, 960, 320, 300, 960, 300, 960, 980, 300, 300, 960, 320, 960, 300,
-            10080, 320, 960, 960, 320, 300, 960, 300, 960, 980, 300, 300, 960, 320, 960, 300, 960,
-            960, 320, 300, 960, 960, 320, 960, 300, 300, 960, 320, 960, 960, 300, 320, 960, 960,
-            300, 320, 960, 960, 300, 320, 960, 300, 960, 960, 320, 300, 960, 320, 960, 300, 10080,
-            320, 960, 960, 320, 300, 960, 300, 960, 960, 320, 300, 960, 320, 960, 300, 960, 960,
-            320, 300, 960, 960, 320, 960, 300, 320, 960, 300, 960, 960, 320, 300, 960, 960, 320,
-            300, 960, 960, 320, 300, 960, 300, 960, 980, 300, 300, 960, 320, 960, 300, 10100, 300,
-            980, 960, 300, 300, 960, 320, 960, 960, 300, 320, 960, 300, 960, 300, 980, 960, 300,
-            320, 960, 960, 300, 960, 320, 300, 960, 320, 960, 960, 300, 320, 960, 960, 300, 320,
-            960, 960, 300, 320, 960, 300, 960, 960, 320, 300, 960, 300, 960, 320, 10100, 300, 960,
-            960, 320, 300, 960, 320, 940, 980, 300, 300, 980, 300, 960, 300, 960, 980, 300, 300,
-            960, 960, 320, 960, 320, 300, 960, 300, 960, 980, 300, 300, 960, 960, 320, 300, 960,
-            980, 300, 300, 960, 320, 960, 960, 300, 320, 960, 300, 960, 320, 10080, 320, 960, 960,
-            300, 320, 960, 300, 960, 960, 320, 300, 960, 320, 960, 300, 960, 960, 320, 300, 960,
-            960, 320, 960, 300, 320, 960, 300, 960, 960, 320, 300, 960, 960, 320, 300, 960, 960,
-            320, 300, 960, 320, 960, 960, 300, 320, 960, 300,
-        ]);
-        assert_eq!(
-            decoded,
-            Ok(Code {
-                value: 0x48b2a4,
-                length: 24
-            })
-        );
+    fn frame_end(&mut self, end: FrameEnd) {
+        self.end = Some(end);
     }
+}
 
-    #[cfg(feature = "serde")]
-    #[test]
-    fn serde_code() {
-        use serde_test::{assert_tokens, Token};
+/// A callback to observe how the decoder treats each pulse, set with `DecodeOptions::on_pulse`.
+///
+/// The callback is called for each pulse as the decoder examines it, in order. A pulse may be
+/// reported more than once if the decoder examines it again, such as when searching for the start
+/// of the next frame after a frame fails to decode. The break which ends a frame is reported when
+/// searching for the start of the next frame, so functions which only decode the first frame don't
+/// report it.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct PulseObserver(Arc<dyn Fn(PulseEvent) + Send + Sync>);
 
-        assert_tokens(
-            &Code {
-                value: 0,
-                length: 12,
-            },
-            &[Token::Str("000")],
-        );
-        assert_tokens(
-            &Code {
-                value: 0xf,
-                length: 4,
-            },
-            &[Token::Str("f")],
-        );
-        assert_tokens(
-            &Code {
-                value: 0x123456,
-                length: 24,
-            },
-            &[Token::Str("123456")],
-        );
-        assert_tokens(
-            &Code {
-                value: 0xabcdef,
-                length: 24,
-            },
-            &[Token::Str("abcdef")],
-        );
-        assert_tokens(
-            &Code {
-                value: 0xff112233,
-                length: 32,
-            },
-            &[Token::Str("ff112233")],
-        );
+#[cfg(feature = "alloc")]
+impl PulseObserver {
+    /// Creates a new observer which calls the given callback for each pulse.
+    pub fn new(callback: impl Fn(PulseEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    fn observe(&self, index: usize, duration: u16, kind: PulseKind) {
+        (self.0)(PulseEvent {
+            index,
+            duration,
+            kind,
+        });
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Debug for PulseObserver {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("PulseObserver").finish_non_exhaustive()
+    }
+}
+
+/// Observers are equal if they are clones of the same observer.
+#[cfg(feature = "alloc")]
+impl PartialEq for PulseObserver {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Eq for PulseObserver {}
+
+/// How the decoder treated a single pulse, passed to a [`PulseObserver`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PulseEvent {
+    /// The index of the pulse in the pulses being decoded.
+    pub index: usize,
+    /// The duration of the pulse in microseconds.
+    pub duration: u16,
+    /// How the pulse was treated.
+    pub kind: PulseKind,
+}
+
+/// How the decoder treated a single pulse.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PulseKind {
+    /// The pulse was skipped while searching for the start of a frame.
+    IgnoredBeforeStart,
+    /// The pulse was part of a valid symbol, and was the short pulse duration.
+    Short,
+    /// The pulse was part of a valid symbol, and was a longer multiple of the short pulse duration.
+    Long,
+    /// The pulse was long enough to start or end a frame.
+    Break,
+    /// The pulse was part of a pair which isn't a valid symbol, even if a bit was guessed for it.
+    Rejected,
+}
+
+/// Details of a decoded frame, returned by [`decode_detailed`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DecodeDetails {
+    /// The decoded code.
+    pub code: Code,
+    /// The positions of bits which were guessed, counting from 0 for the first (most significant)
+    /// bit received, as for [`decode_with_uncertainty`].
+    pub uncertain: Vec<u8>,
+    /// How the frame ended.
+    pub end: FrameEnd,
+    /// The exact pulses of the frame which was decoded, starting with the break or sync pair before
+    /// it and ending with the break after it if there was one.
+    ///
+    /// These can be passed to [`decode_with`] again, or retransmitted verbatim for receivers which
+    /// don't accept the idealised timing of [`Code::to_pulses`].
+    pub pulses: Vec<u16>,
+    /// The short pulse duration in microseconds which was inferred for the frame.
+    pub short_duration: u16,
+}
+
+/// How a decoded frame ended.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FrameEnd {
+    /// The frame ended with a break pulse.
+    Break,
+    /// The frame ended after `DecodeOptions::expected_length` bits.
+    ExpectedLength,
+    /// The pulses ran out after a complete symbol, without a final break. The capture may have
+    /// ended early, but the last bit is still complete.
+    Complete,
+    /// The pulses ran out in the middle of a symbol, so there was a final high pulse without a low
+    /// pulse after it. It was ignored.
+    Truncated,
+}
+
+/// Given a sequence of pulse durations in nanoseconds (starting with a high pulse), try to decode a
+/// button code using the given options.
+///
+/// This is the same as [`decode_with`], but avoids losing precision by rounding pulse durations to
+/// microseconds. Durations in the options and in errors are still in microseconds.
+pub fn decode_nanos(pulses: &[u32], options: &DecodeOptions) -> Result<Code, Error> {
+    decode_scaled(pulses, options, 1000, &mut ()).map(|(_, frame)| frame.code)
+}
+
+/// Decodes a sequence of pulse durations in units of `1 / units_per_micro` microseconds.
+///
+/// Returns the range of pulses which make up the frame, including the break or sync pair before it,
+/// along with the decoded frame. Details of the last frame which was tried are recorded to
+/// `diagnostics`, even if decoding fails.
+fn decode_scaled<T: Copy + Into<u64>, D: RecordDiagnostics>(
+    pulses: &[T],
+    options: &DecodeOptions,
+    units_per_micro: u64,
+    diagnostics: &mut D,
+) -> Result<(Range<usize>, Frame), Error> {
+    if pulses.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+
+    // Find the start of the first frame, skipping frames which are too short.
+    let mut search_from = 0;
+    let mut first_error = None;
+    loop {
+        let Some(start) = next_start(pulses, search_from, options, units_per_micro) else {
+            return Err(if search_from == 0 {
+                Error::NoStart
+            } else {
+                first_error.unwrap_or(Error::TooShort)
+            });
+        };
+        trace!("Found start of frame at pulse {}", start);
+        diagnostics.frame_start(start);
+        let frame = match decode_frame_scaled(
+            &pulses[start..],
+            start,
+            options,
+            units_per_micro,
+            diagnostics,
+        ) {
+            Ok(frame) => frame,
+            // A partial frame may also be invalid, so if short frames are being skipped then skip
+            // invalid ones too.
+            Err(e) if options.required_bits() > 0 => {
+                trace!("Skipping invalid frame at pulse {}: {}", start, e);
+                first_error.get_or_insert(e);
+                search_from = (start + 1).max(search_from + 1).min(pulses.len());
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if frame.code.length >= options.required_bits() {
+            verify_checksum(frame.code, options)?;
+            let start_length = match options.start {
+                Start::Break => 1,
+                Start::HighBreak | Start::SyncPair(..) => 2,
+            };
+            // There may be no start pulses if `options.assume_start` is set.
+            let range =
+                (start - options.pair_offset).saturating_sub(start_length)..start + frame.length;
+            return Ok((range, frame));
+        }
+        // The search must move on even if the frame was assumed to start at the beginning.
+        search_from = (start + frame.next).max(search_from + 1);
+    }
+}
+
+/// Returns the index of the first pulse of the first frame which starts at or after `from`, or
+/// `None` if there are no more frames.
+///
+/// The index is after any pulses skipped because of `options.pair_offset`. If
+/// `options.validate_start` is set then frames which don't start with two valid symbols are
+/// skipped.
+fn next_start<T: Copy + Into<u64>>(
+    pulses: &[T],
+    mut from: usize,
+    options: &DecodeOptions,
+    units_per_micro: u64,
+) -> Option<usize> {
+    let search_from = from;
+    let mut assume_start = from == 0 && options.assume_start;
+    loop {
+        let found = find_start(pulses, from, options, units_per_micro);
+        let start = if assume_start {
+            assume_start = false;
+            // Unless the first pair of pulses is a start, the first frame starts at the first high
+            // pulse.
+            let beginning = options.polarity.next_high(0);
+            found
+                .filter(|&start| start <= beginning + 2)
+                .unwrap_or(beginning)
+        } else if let Some(start) = found {
+            start
+        } else {
+            observe_skipped(pulses, search_from..pulses.len(), options, units_per_micro);
+            return None;
+        };
+        let start = (start + options.pair_offset).min(pulses.len());
+        if !options.validate_start || valid_start(&pulses[start..], options, units_per_micro) {
+            observe_skipped(pulses, search_from..start, options, units_per_micro);
+            return Some(start);
+        }
+        from = start;
+    }
+}
+
+/// Reports the given range of pulses, which were skipped while searching for the start of a frame,
+/// to `options.on_pulse` if it is set.
+// There is no `options.on_pulse` without `alloc`, so nothing to do.
+#[cfg_attr(not(feature = "alloc"), allow(unused_variables))]
+fn observe_skipped<T: Copy + Into<u64>>(
+    pulses: &[T],
+    range: Range<usize>,
+    options: &DecodeOptions,
+    units_per_micro: u64,
+) {
+    #[cfg(feature = "alloc")]
+    if let Some(observer) = &options.on_pulse {
+        let break_pulse_length = u64::from(options.break_pulse_length) * units_per_micro;
+        for (index, &pulse) in pulses.iter().enumerate().take(range.end).skip(range.start) {
+            let pulse = pulse.into();
+            let kind = if pulse > break_pulse_length {
+                PulseKind::Break
+            } else {
+                PulseKind::IgnoredBeforeStart
+            };
+            observer.observe(index, to_micros(pulse, units_per_micro), kind);
+        }
+    }
+}
+
+/// Returns the index of the first pulse after the first start pulse or sync pair at or after
+/// `from`.
+fn find_start<T: Copy + Into<u64>>(
+    pulses: &[T],
+    from: usize,
+    options: &DecodeOptions,
+    units_per_micro: u64,
+) -> Option<usize> {
+    match options.start {
+        Start::Break => {
+            let break_pulse_length = u64::from(options.break_pulse_length) * units_per_micro;
+            pulses[from..]
+                .iter()
+                .position(|&pulse| pulse.into() > break_pulse_length)
+                .map(|start| from + start + 1)
+        }
+        Start::HighBreak => {
+            let break_pulse_length = u64::from(options.break_pulse_length) * units_per_micro;
+            (options.polarity.next_high(from)..pulses.len())
+                .step_by(2)
+                .find(|&i| pulses[i].into() > break_pulse_length)
+                .map(|start| start + 2)
+        }
+        Start::SyncPair(high_multiple, low_multiple) => {
+            let (high_multiple, low_multiple) = (u64::from(high_multiple), u64::from(low_multiple));
+            (options.polarity.next_high(from)..pulses.len().saturating_sub(1))
+                .step_by(2)
+                .find(|&i| {
+                    let (high, low): (u64, u64) = (pulses[i].into(), pulses[i + 1].into());
+                    // A sync pair of zero multiples can't match anything.
+                    (high + low)
+                        .checked_div(high_multiple + low_multiple)
+                        .is_some_and(|short_duration| {
+                            short_duration != 0
+                                && round_div(high, short_duration) == high_multiple
+                                && round_div(low, short_duration) == low_multiple
+                        })
+                })
+                .map(|start| start + 2)
+        }
+    }
+}
+
+/// Puts the bits of a code received most significant bit first into the order given by
+/// `options.bit_order`.
+fn apply_bit_order(code: Code, options: &DecodeOptions) -> Code {
+    match options.bit_order {
+        BitOrder::MsbFirst => code,
+        BitOrder::LsbFirst => code.reversed(),
+    }
+}
+
+/// Returns the given code if it passes the checksum configured in the options, if any.
+fn verify_checksum(code: Code, options: &DecodeOptions) -> Result<Code, Error> {
+    match options.checksum {
+        Some(checksum) if !checksum.verify(&code) => Err(Error::ChecksumFailed),
+        _ => Ok(code),
+    }
+}
+
+/// A frame decoded by [`decode_symbols`].
+struct Frame {
+    /// The decoded code.
+    code: Code,
+    /// The index within the frame from which to search for the start of the next frame, so that
+    /// the pulses of the frame don't need to be scanned again.
+    next: usize,
+    /// A mask of the positions of bits which were guessed, with the least significant bit for the
+    /// first bit received.
+    uncertain: u128,
+    /// How the frame ended.
+    end: FrameEnd,
+    /// The number of pulses of the frame which were decoded, including any break which ended it.
+    length: usize,
+    /// The short pulse duration which was inferred for the frame, in the same units as the pulses.
+    #[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+    short_duration: u64,
+}
+
+/// Decodes a single frame of pulse durations following a break, in units of
+/// `1 / units_per_micro` microseconds.
+///
+/// `offset` is the index of the first pulse of the frame within the whole capture, for reporting to
+/// `options.on_pulse`.
+fn decode_frame_scaled<T: Copy + Into<u64>, D: RecordDiagnostics>(
+    pulses: &[T],
+    offset: usize,
+    options: &DecodeOptions,
+    units_per_micro: u64,
+    diagnostics: &mut D,
+) -> Result<Frame, Error> {
+    if pulses.len() < 4 {
+        return Err(Error::TooShort);
+    }
+    let short_duration =
+        frame_short_duration(pulses, options, units_per_micro).ok_or(Error::NoSignal)?;
+    trace!(
+        "Short pulse duration {} μs",
+        to_micros(short_duration, units_per_micro)
+    );
+    diagnostics.short_duration(to_micros(short_duration, units_per_micro));
+    let frame = decode_symbols(
+        pulses,
+        offset,
+        short_duration,
+        options,
+        units_per_micro,
+        diagnostics,
+    )?;
+    diagnostics.frame_end(frame.end);
+    if options.require_trailing_break
+        && matches!(frame.end, FrameEnd::Complete | FrameEnd::Truncated)
+    {
+        return Err(Error::NoEnd);
+    }
+    let mut frame = strip_sync_word(frame, options)?;
+    frame.code = apply_bit_order(frame.code, options);
+    Ok(frame)
+}
+
+/// Checks that the given frame starts with the sync word from the options, if any, and removes it.
+fn strip_sync_word(mut frame: Frame, options: &DecodeOptions) -> Result<Frame, Error> {
+    let Some((sync_value, sync_bits)) = options.sync_word else {
+        return Ok(frame);
+    };
+    let payload_bits = frame
+        .code
+        .length
+        .checked_sub(sync_bits)
+        .ok_or(Error::SyncMismatch)?;
+    if frame
+        .code
+        .value
+        .checked_shr(payload_bits.into())
+        .unwrap_or(0)
+        != u128::from(sync_value)
+    {
+        return Err(Error::SyncMismatch);
+    }
+    frame.code = frame.code.truncated_to(payload_bits)?;
+    frame.uncertain = frame.uncertain.checked_shr(sync_bits.into()).unwrap_or(0);
+    Ok(frame)
+}
+
+/// Decodes the symbols of a single frame of pulse durations following a break with the given
+/// short pulse duration, all in units of `1 / units_per_micro` microseconds.
+///
+/// `offset` is the index of the first pulse of the frame within the whole capture, for reporting to
+/// `options.on_pulse`. Each symbol is recorded to `diagnostics`.
+// `offset` is unused without `alloc`, as there is no `options.on_pulse` to report to.
+#[cfg_attr(not(feature = "alloc"), allow(unused_variables))]
+fn decode_symbols<T: Copy + Into<u64>, D: RecordDiagnostics>(
+    pulses: &[T],
+    offset: usize,
+    short_duration: u64,
+    options: &DecodeOptions,
+    units_per_micro: u64,
+    diagnostics: &mut D,
+) -> Result<Frame, Error> {
+    let break_pulse_length = u64::from(options.break_pulse_length) * units_per_micro;
+    let frame_end_length = options
+        .break_multiple
+        .map_or(break_pulse_length, |multiple| {
+            short_duration.saturating_mul(multiple.into())
+        });
+
+    let mut value: u128 = 0;
+    let mut length = 0;
+    let mut uncertain = 0;
+    // The index of the first pair containing a pulse long enough to be a break.
+    let mut first_break = None;
+    // The index of the first pulse which wasn't decoded.
+    let mut end = pulses.len() - pulses.len() % 2;
+    let mut frame_end = if pulses.len().is_multiple_of(2) {
+        FrameEnd::Complete
+    } else {
+        FrameEnd::Truncated
+    };
+    // The number of pulses decoded, including any break at the end.
+    let mut decoded = pulses.len();
+    let mut preamble_left = options.preamble_bits;
+    let short_durations = high_low_short_durations(pulses, short_duration, options);
+    let classifier = SymbolClassifier::new(short_durations, options);
+    let startup_classifier = SymbolClassifier::startup(short_durations, options);
+    // The expected length doesn't include the sync word.
+    let expected_length = options.expected_length.map(|expected_length| {
+        u16::from(expected_length) + u16::from(options.sync_word.map_or(0, |(_, bits)| bits))
+    });
+    // Fails to decode the frame because of an invalid symbol, unless `options.resync_on_error` is
+    // set in which case the bit is guessed so as to carry on from the next pair of pulses.
+    let invalid_symbol = |high: u64, low: u64| {
+        if options.resync_on_error {
+            Ok((options.resync_placeholder, true))
+        } else {
+            Err(Error::InvalidPulseLength(
+                to_micros(high, units_per_micro),
+                to_micros(low, units_per_micro),
+            ))
+        }
+    };
+    for (i, pair) in pulses.chunks_exact(2).enumerate() {
+        if expected_length == Some(length.into()) {
+            trace!("Stopping after expected {} bits", length);
+            end = 2 * i;
+            decoded = end;
+            frame_end = FrameEnd::ExpectedLength;
+            break;
+        }
+        let (high, low) = (pair[0].into(), pair[1].into());
+        let is_break = high > break_pulse_length || low > break_pulse_length;
+        if is_break {
+            first_break.get_or_insert(2 * i);
+        }
+        let class = match &startup_classifier {
+            Some(startup) if i < usize::from(options.startup_symbols) => startup,
+            _ => &classifier,
+        }
+        .classify(high, low);
+        if class.is_none() && (high > frame_end_length || low > frame_end_length) {
+            trace!("Frame ended after {} bits", length);
+            frame_end = FrameEnd::Break;
+            decoded = 2 * i + 2;
+            break;
+        }
+        diagnostics.symbol(|| SymbolDiagnostics {
+            high: to_micros(high, units_per_micro),
+            low: to_micros(low, units_per_micro),
+            bit: class,
+            error: to_micros(
+                symbol_error(high, low, short_durations, options, class),
+                units_per_micro,
+            ),
+        });
+        #[cfg(feature = "alloc")]
+        if let Some(observer) = &options.on_pulse {
+            let multiples = match class {
+                Some(true) => Some(options.one),
+                Some(false) => Some(options.zero),
+                None => None,
+            };
+            let kind = |multiple: Option<u8>| match multiple {
+                None => PulseKind::Rejected,
+                Some(0 | 1) => PulseKind::Short,
+                Some(_) => PulseKind::Long,
+            };
+            let index = offset + 2 * i;
+            observer.observe(
+                index,
+                to_micros(high, units_per_micro),
+                kind(multiples.map(|(high, _)| high)),
+            );
+            observer.observe(
+                index + 1,
+                to_micros(low, units_per_micro),
+                kind(multiples.map(|(_, low)| low)),
+            );
+        }
+        // The bit, and whether it was guessed.
+        let (bit, guessed) = if let Some(bit) = class {
+            trace!(
+                "Bit {}: {} μs high {} μs low is {}",
+                length,
+                to_micros(high, units_per_micro),
+                to_micros(low, units_per_micro),
+                u8::from(bit)
+            );
+            (bit, false)
+        } else if (
+            round_div(high, short_duration),
+            round_div(low, short_duration),
+        ) == (2, 2)
+        {
+            trace!(
+                "Bit {}: {} μs high {} μs low is ambiguous",
+                length,
+                to_micros(high, units_per_micro),
+                to_micros(low, units_per_micro)
+            );
+            match options.ambiguity {
+                AmbiguityPolicy::Abort => invalid_symbol(high, low)?,
+                AmbiguityPolicy::Skip => continue,
+                AmbiguityPolicy::Majority => {
+                    // Guess whichever bit has been seen more often so far, or 0 if it is a tie.
+                    (value.count_ones() * 2 > u32::from(length), true)
+                }
+            }
+        } else {
+            trace!(
+                "Bit {}: {} μs high {} μs low is not a valid symbol",
+                length,
+                to_micros(high, units_per_micro),
+                to_micros(low, units_per_micro)
+            );
+            invalid_symbol(high, low)?
+        };
+        if preamble_left > 0 {
+            trace!("Skipping preamble bit");
+            preamble_left -= 1;
+            continue;
+        }
+        if u32::from(length) >= u128::BITS {
+            return Err(Error::TooLong);
+        }
+        if guessed {
+            uncertain |= 1 << length;
+        }
+        value = value << 1 | u128::from(bit);
+        length += 1;
+    }
+
+    let next = match options.start {
+        // No pulse before the first break can start a frame.
+        Start::Break | Start::HighBreak => first_break.unwrap_or(end),
+        // Sync pairs aren't tracked while decoding, so the whole frame must be searched again.
+        Start::SyncPair(..) => 0,
+    };
+    Ok(Frame {
+        code: Code { value, length },
+        next,
+        uncertain,
+        end: frame_end,
+        length: decoded,
+        short_duration,
+    })
+}
+
+/// Returns the short pulse duration to decode a frame with, in units of `1 / units_per_micro`
+/// microseconds.
+///
+/// This is `options.short_duration` if it is set, or otherwise estimated from the pulses of the
+/// frame. Returns `None` if it is 0.
+fn frame_short_duration<T: Copy + Into<u64>>(
+    pulses: &[T],
+    options: &DecodeOptions,
+    units_per_micro: u64,
+) -> Option<u64> {
+    match options.short_duration {
+        Some(short_duration) => {
+            Some(u64::from(short_duration) * units_per_micro).filter(|&duration| duration != 0)
+        }
+        None => estimate_short_duration(pulses, options, units_per_micro),
+    }
+}
+
+/// The maximum number of times to refine the short pulse duration in
+/// [`estimate_short_duration`].
+const MAX_CLOCK_ITERATIONS: usize = 8;
+
+/// Calculates the short pulse duration of a frame from all of its data pulses, in units of
+/// `1 / units_per_micro` microseconds.
+///
+/// This starts from the average length of the pulses, assuming that there are as many 1s as 0s.
+/// Each pulse is then assigned to the nearest of the multiples used by the symbols, and the short
+/// pulse duration recalculated as the total length of the pulses divided by the total of their
+/// multiples, until it stops changing. A single distorted pulse therefore only has a small effect
+/// on the result.
+///
+/// The frame ends at the first pulse longer than the break pulse length, or the break multiple of
+/// the estimate from [`first_symbols_short_duration`] if `options.break_multiple` is set. If it has
+/// fewer than 4 pulses then the estimate from the first symbols is used instead.
+///
+/// Returns `None` if there are fewer than 4 pulses, or they are too short.
+fn estimate_short_duration<T: Copy + Into<u64>>(
+    pulses: &[T],
+    options: &DecodeOptions,
+    units_per_micro: u64,
+) -> Option<u64> {
+    let first_estimate = first_symbols_short_duration(pulses, options)?;
+    let frame_end_length = match options.break_multiple {
+        Some(multiple) => first_estimate.saturating_mul(multiple.into()),
+        None => u64::from(options.break_pulse_length) * units_per_micro,
+    };
+    let mut end = pulses
+        .iter()
+        .position(|&pulse| pulse.into() > frame_end_length)
+        .unwrap_or(pulses.len());
+    // Ignore any noise after the expected end of the frame.
+    if let Some(expected_length) = options.expected_length {
+        let symbols = usize::from(options.preamble_bits)
+            + usize::from(expected_length)
+            + usize::from(options.sync_word.map_or(0, |(_, bits)| bits));
+        end = end.min(2 * symbols);
+    }
+    let frame = &pulses[..end];
+    if frame.len() < 4 {
+        return Some(first_estimate);
+    }
+
+    let multiples = [options.one.0, options.one.1, options.zero.0, options.zero.1];
+    let multiples_sum: u64 = multiples.iter().copied().map(u64::from).sum();
+    let total: u64 = frame.iter().map(|&pulse| pulse.into()).sum();
+    let mut short_duration = (total * 4).checked_div(frame.len() as u64 * multiples_sum)?;
+    for _ in 0..MAX_CLOCK_ITERATIONS {
+        if short_duration == 0 {
+            break;
+        }
+        let nearest_multiple = |pulse: u64| {
+            multiples
+                .iter()
+                .copied()
+                .map(u64::from)
+                .min_by_key(|&multiple| pulse.abs_diff(multiple * short_duration))
+                .unwrap_or(0)
+        };
+        let multiples_total: u64 = frame
+            .iter()
+            .map(|&pulse| nearest_multiple(pulse.into()))
+            .sum();
+        if multiples_total == 0 {
+            break;
+        }
+        let next = round_div(total, multiples_total);
+        if next == short_duration {
+            break;
+        }
+        short_duration = next;
+    }
+    Some(short_duration).filter(|&duration| duration != 0)
+}
+
+/// Uses the first 4 pulses of a frame to calculate the short pulse duration, assuming that the
+/// first two symbols are on average as long as a 1 and a 0.
+///
+/// Returns `None` if there are fewer than 4 pulses, they are too short, or the pulse length
+/// multiples are all 0.
+fn first_symbols_short_duration<T: Copy + Into<u64>>(
+    pulses: &[T],
+    options: &DecodeOptions,
+) -> Option<u64> {
+    let multiples_sum = u64::from(options.one.0)
+        + u64::from(options.one.1)
+        + u64::from(options.zero.0)
+        + u64::from(options.zero.1);
+    pulses
+        .get(0..4)?
+        .iter()
+        .map(|&pulse| pulse.into())
+        .sum::<u64>()
+        .checked_div(multiples_sum)
+        .filter(|&duration| duration != 0)
+}
+
+/// Returns the short pulse durations to classify high and low pulses with respectively.
+///
+/// These are both the given short pulse duration unless `DecodeOptions::separate_high_low` is set,
+/// in which case each is estimated from the corresponding pulses of the first two symbols.
+fn high_low_short_durations<T: Copy + Into<u64>>(
+    pulses: &[T],
+    short_duration: u64,
+    options: &DecodeOptions,
+) -> (u64, u64) {
+    if !options.separate_high_low || options.short_duration.is_some() {
+        return (short_duration, short_duration);
+    }
+    let Some(&[high0, low0, high1, low1]) = pulses.get(0..4) else {
+        return (short_duration, short_duration);
+    };
+    let estimate = |first: T, second: T, multiples_sum: u64| {
+        (first.into() + second.into())
+            .checked_div(multiples_sum)
+            .filter(|&duration| duration != 0)
+    };
+    match (
+        estimate(
+            high0,
+            high1,
+            u64::from(options.one.0) + u64::from(options.zero.0),
+        ),
+        estimate(
+            low0,
+            low1,
+            u64::from(options.one.1) + u64::from(options.zero.1),
+        ),
+    ) {
+        (Some(high_short), Some(low_short)) => (high_short, low_short),
+        _ => (short_duration, short_duration),
+    }
+}
+
+/// Classifies pairs of pulses as symbols for given short pulse durations of high and low pulses.
+///
+/// By default a pair of pulses is a symbol if each pulse rounds to the right multiple of the short
+/// pulse duration. Rather than dividing each pulse by the short duration, they are compared against
+/// precomputed bounds, which is much faster for long captures.
+struct SymbolClassifier {
+    /// Half the short pulse durations of high and low pulses, added to each pulse so that it rounds
+    /// to the nearest multiple.
+    half: (u64, u64),
+    /// The inclusive bounds of the high and low pulses of a 1, after adding `half`.
+    one: ((u64, u64), (u64, u64)),
+    /// The inclusive bounds of the high and low pulses of a 0, after adding `half`.
+    zero: ((u64, u64), (u64, u64)),
+    /// The ideal symbols to compare against instead, if `DecodeOptions::tolerance` is set.
+    nearest: Option<NearestSymbol>,
+}
+
+/// Classifies pairs of pulses as whichever symbol they are closest to, as long as it is within a
+/// tolerance.
+struct NearestSymbol {
+    /// The ideal durations of the high and low pulses of a 1.
+    one: (u64, u64),
+    /// The ideal durations of the high and low pulses of a 0.
+    zero: (u64, u64),
+    /// The maximum total difference from the ideal durations, as a percentage of the duration of
+    /// the symbol.
+    tolerance: u64,
+}
+
+impl NearestSymbol {
+    fn classify(&self, high: u64, low: u64) -> Option<bool> {
+        let error = |(ideal_high, ideal_low): (u64, u64)| {
+            high.abs_diff(ideal_high)
+                .saturating_add(low.abs_diff(ideal_low))
+        };
+        let within_tolerance = |error: u64, (ideal_high, ideal_low): (u64, u64)| {
+            error.saturating_mul(100)
+                <= ideal_high
+                    .saturating_add(ideal_low)
+                    .saturating_mul(self.tolerance)
+        };
+        let (one_error, zero_error) = (error(self.one), error(self.zero));
+        if one_error < zero_error && within_tolerance(one_error, self.one) {
+            Some(true)
+        } else if zero_error < one_error && within_tolerance(zero_error, self.zero) {
+            Some(false)
+        } else {
+            // Either it is too far from both, or it is exactly halfway between them.
+            None
+        }
+    }
+}
+
+impl SymbolClassifier {
+    fn new(short_durations: (u64, u64), options: &DecodeOptions) -> Self {
+        Self::with_tolerance(short_durations, options, options.tolerance)
+    }
+
+    /// Returns a classifier for the first `options.startup_symbols` symbols of a frame, or `None`
+    /// if they should be classified in the same way as the rest.
+    fn startup(short_durations: (u64, u64), options: &DecodeOptions) -> Option<Self> {
+        options
+            .startup_tolerance
+            .filter(|_| options.startup_symbols > 0)
+            .map(|tolerance| Self::with_tolerance(short_durations, options, Some(tolerance)))
+    }
+
+    fn with_tolerance(
+        (high_short, low_short): (u64, u64),
+        options: &DecodeOptions,
+        tolerance: Option<u8>,
+    ) -> Self {
+        let bounds = |multiple: u8, short_duration: u64| {
+            let multiple = u64::from(multiple);
+            // Bounds which no pulse is within, for multiples which `round_div` can never return.
+            let empty = (1, 0);
+            let Some(min) = multiple.checked_mul(short_duration) else {
+                return empty;
+            };
+            match (multiple + 1).checked_mul(short_duration) {
+                None => (min, u64::MAX),
+                Some(0) => empty,
+                Some(end) => (min, end - 1),
+            }
+        };
+        let pair_bounds =
+            |(high, low): (u8, u8)| (bounds(high, high_short), bounds(low, low_short));
+        let ideal = |(high, low): (u8, u8)| {
+            (
+                u64::from(high).saturating_mul(high_short),
+                u64::from(low).saturating_mul(low_short),
+            )
+        };
+        Self {
+            half: (high_short / 2, low_short / 2),
+            one: pair_bounds(options.one),
+            zero: pair_bounds(options.zero),
+            nearest: tolerance.map(|tolerance| NearestSymbol {
+                one: ideal(options.one),
+                zero: ideal(options.zero),
+                tolerance: tolerance.into(),
+            }),
+        }
+    }
+
+    /// Returns the bit which the given pair of pulses represents, or `None` if it is not a valid
+    /// symbol.
+    fn classify(&self, high: u64, low: u64) -> Option<bool> {
+        if let Some(nearest) = &self.nearest {
+            return nearest.classify(high, low);
+        }
+        let within = |pulse: u64, (min, max): (u64, u64)| (min..=max).contains(&pulse);
+        let (high, low) = (
+            high.saturating_add(self.half.0),
+            low.saturating_add(self.half.1),
+        );
+        if within(high, self.one.0) && within(low, self.one.1) {
+            Some(true)
+        } else if within(high, self.zero.0) && within(low, self.zero.1) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the total difference of the given pulses from the ideal durations for the given bit, or
+/// for whichever bit they are closer to if it is `None`.
+fn symbol_error(
+    high: u64,
+    low: u64,
+    (high_short, low_short): (u64, u64),
+    options: &DecodeOptions,
+    bit: Option<bool>,
+) -> u64 {
+    let error = |(high_multiple, low_multiple): (u8, u8)| {
+        high.abs_diff(u64::from(high_multiple).saturating_mul(high_short))
+            .saturating_add(low.abs_diff(u64::from(low_multiple).saturating_mul(low_short)))
+    };
+    match bit {
+        Some(true) => error(options.one),
+        Some(false) => error(options.zero),
+        None => error(options.one).min(error(options.zero)),
+    }
+}
+
+/// Returns whether the first two pairs of pulses of a frame are valid symbols.
+fn valid_start<T: Copy + Into<u64>>(
+    pulses: &[T],
+    options: &DecodeOptions,
+    units_per_micro: u64,
+) -> bool {
+    let Some(first_pulses) = pulses.get(0..4) else {
+        return false;
+    };
+    frame_short_duration(pulses, options, units_per_micro).is_some_and(|short_duration| {
+        let short_durations = high_low_short_durations(pulses, short_duration, options);
+        let classifier = SymbolClassifier::new(short_durations, options);
+        let startup_classifier = SymbolClassifier::startup(short_durations, options);
+        first_pulses.chunks_exact(2).enumerate().all(|(i, pair)| {
+            match &startup_classifier {
+                Some(startup) if i < usize::from(options.startup_symbols) => startup,
+                _ => &classifier,
+            }
+            .classify(pair[0].into(), pair[1].into())
+            .is_some()
+        })
+    })
+}
+
+/// Converts a duration in units of `1 / units_per_micro` microseconds to microseconds, saturating
+/// at `u16::MAX`.
+fn to_micros(duration: u64, units_per_micro: u64) -> u16 {
+    (duration / units_per_micro).try_into().unwrap_or(u16::MAX)
+}
+
+/// Returns the positions of the bits set in a mask of guessed bits from [`Frame::uncertain`], in
+/// increasing order.
+#[cfg(feature = "alloc")]
+fn uncertain_positions(uncertain: u128) -> Vec<u8> {
+    (0..u128::BITS as u8)
+        .filter(|&position| uncertain >> position & 1 == 1)
+        .collect()
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a button code from every frame following a break pulse.
+///
+/// Remotes usually repeat their code several times for a single button press, so this returns one
+/// result for each repetition, in order.
+#[cfg(feature = "alloc")]
+pub fn decode_all(pulses: &[u16]) -> Vec<Result<Code, Error>> {
+    decode_all_with(pulses, &DecodeOptions::default())
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a button code from every frame following a break pulse, using the given options.
+///
+/// Frames shorter than `options.min_bits` or `options.expected_length` are returned as
+/// [`Error::TooShort`].
+#[cfg(feature = "alloc")]
+pub fn decode_all_with(pulses: &[u16], options: &DecodeOptions) -> Vec<Result<Code, Error>> {
+    decode_all_frames(pulses, options)
+        .into_iter()
+        .map(|frame| frame.map(|frame| frame.code))
+        .collect()
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), decodes every
+/// frame following a break pulse in the same way as [`decode_all_with`], and writes the codes of
+/// those which succeed to `codes` in order.
+///
+/// Returns the number of codes written. Decoding stops once `codes` is full, so frames after that
+/// are ignored. Unlike `decode_all_with` this doesn't allocate, so is suitable for targets without
+/// a heap.
+pub fn decode_all_into(pulses: &[u16], options: &DecodeOptions, codes: &mut [Code]) -> usize {
+    let decoded =
+        Frames::new(pulses, options).filter_map(|frame| frame.ok().map(|frame| frame.code));
+    let mut count = 0;
+    for (slot, code) in codes.iter_mut().zip(decoded) {
+        *slot = code;
+        count += 1;
+    }
+    count
+}
+
+/// Decodes every frame following a break pulse, as for [`decode_all_with`].
+#[cfg(feature = "alloc")]
+fn decode_all_frames(pulses: &[u16], options: &DecodeOptions) -> Vec<Result<Frame, Error>> {
+    Frames::new(pulses, options).collect()
+}
+
+/// An iterator over the results of decoding every frame following a break pulse, as for
+/// [`decode_all_with`].
+struct Frames<'a> {
+    pulses: &'a [u16],
+    options: &'a DecodeOptions,
+    /// The index from which to search for the start of the next frame.
+    search_from: usize,
+}
+
+impl<'a> Frames<'a> {
+    fn new(pulses: &'a [u16], options: &'a DecodeOptions) -> Self {
+        Self {
+            pulses,
+            options,
+            search_from: 0,
+        }
+    }
+}
+
+impl Iterator for Frames<'_> {
+    type Item = Result<Frame, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (pulses, options) = (self.pulses, self.options);
+        // A break at the very end doesn't start a new frame.
+        let start =
+            next_start(pulses, self.search_from, options, 1).filter(|&start| start < pulses.len());
+        let Some(start) = start else {
+            // Don't search again on the next call.
+            self.search_from = pulses.len();
+            return None;
+        };
+        let (result, next) = match decode_frame_scaled(&pulses[start..], start, options, 1, &mut ())
+        {
+            Ok(frame) if frame.code.length < options.required_bits() => {
+                (Err(Error::TooShort), frame.next)
+            }
+            Ok(frame) => {
+                let next = frame.next;
+                (verify_checksum(frame.code, options).map(|_| frame), next)
+            }
+            Err(e) => (Err(e), 0),
+        };
+        // The search must move on even if the frame was assumed to start at the beginning.
+        self.search_from = (start + next).max(self.search_from + 1);
+        Some(result)
+    }
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), returns the
+/// short pulse duration in microseconds which was inferred for each frame, in the same order as
+/// [`decode_all_with`] returns their codes.
+#[cfg(feature = "alloc")]
+pub fn short_durations(pulses: &[u16], options: &DecodeOptions) -> Vec<Result<u16, Error>> {
+    decode_all_frames(pulses, options)
+        .into_iter()
+        .map(|frame| frame.map(|frame| to_micros(frame.short_duration, 1)))
+        .collect()
+}
+
+/// A change in the inferred short pulse duration between consecutive frames, found by
+/// [`detect_drift`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Drift {
+    /// The index of the frame whose short pulse duration changed, as for [`decode_all_with`].
+    pub frame: usize,
+    /// The short pulse duration in microseconds of the previous frame which was decoded.
+    pub previous: u16,
+    /// The short pulse duration in microseconds of this frame.
+    pub current: u16,
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), finds every
+/// frame whose inferred short pulse duration differs from that of the previous decoded frame by
+/// more than `threshold_pct` percent of it.
+///
+/// The short pulse duration should be stable across the repeated frames of a single button press,
+/// so drift may be a sign of poor reception. Frames which fail to decode are ignored.
+#[cfg(feature = "alloc")]
+pub fn detect_drift(pulses: &[u16], options: &DecodeOptions, threshold_pct: u8) -> Vec<Drift> {
+    let mut drifts = Vec::new();
+    let mut previous = None;
+    for (frame, short_duration) in short_durations(pulses, options).into_iter().enumerate() {
+        let Ok(current) = short_duration else {
+            continue;
+        };
+        if let Some(previous) = previous {
+            if u32::from(current.abs_diff(previous)) * 100
+                > u32::from(previous) * u32::from(threshold_pct)
+            {
+                drifts.push(Drift {
+                    frame,
+                    previous,
+                    current,
+                });
+            }
+        }
+        previous = Some(current);
+    }
+    drifts
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), returns a
+/// quality score for each frame in order.
+///
+/// The score is from 0 to 1, where 1 means that every pulse of the frame was exactly a multiple of
+/// the short pulse duration, and 0 means that the pulses were on average as far as possible from a
+/// multiple, or that the frame couldn't be decoded. A declining trend may be a sign of a remote's
+/// battery running low.
+#[cfg(feature = "alloc")]
+pub fn quality_trend(pulses: &[u16]) -> Vec<f32> {
+    let options = DecodeOptions::default();
+    let mut scores = Vec::new();
+    let mut search_from = 0;
+    while let Some(start) =
+        next_start(pulses, search_from, &options, 1).filter(|&start| start < pulses.len())
+    {
+        let frame = &pulses[start..];
+        search_from = start;
+        let score = match decode_frame_scaled(frame, start, &options, 1, &mut ()) {
+            Ok(decoded) if decoded.code.length > 0 => {
+                search_from += decoded.next;
+                frame_quality(&frame[..2 * usize::from(decoded.code.length)], &options)
+            }
+            _ => 0.0,
+        };
+        scores.push(score);
+    }
+    scores
+}
+
+/// Returns how close the pulses of a decoded frame are to multiples of the short pulse duration,
+/// from 0 to 1.
+#[cfg(feature = "alloc")]
+fn frame_quality(pulses: &[u16], options: &DecodeOptions) -> f32 {
+    let Some(short_duration) = estimate_short_duration(pulses, options, 1) else {
+        return 0.0;
+    };
+    // Each pulse is at most half the short pulse duration from the nearest multiple.
+    let total_error: u64 = pulses
+        .iter()
+        .map(|&pulse| {
+            let pulse = u64::from(pulse);
+            pulse.abs_diff(round_div(pulse, short_duration) * short_duration)
+        })
+        .sum();
+    let max_error = pulses.len() as f32 * short_duration as f32 / 2.0;
+    (1.0 - total_error as f32 / max_error).clamp(0.0, 1.0)
+}
+
+/// Given a sequence of pulses as pairs of level (true for high) and duration, try to decode a button
+/// code.
+///
+/// Consecutive pulses with the same level are merged, so a dropped edge doesn't shift the pairing
+/// of subsequent pulses.
+#[cfg(feature = "alloc")]
+pub fn decode_edges(edges: &[(bool, Duration)]) -> Result<Code, Error> {
+    let mut pulses: Vec<u16> = Vec::with_capacity(edges.len());
+    let mut last_level = None;
+    for &(level, duration) in edges {
+        let micros = duration.as_micros().try_into().unwrap_or(u16::MAX);
+        match pulses.last_mut() {
+            Some(last) if last_level == Some(level) => *last = last.saturating_add(micros),
+            _ => pulses.push(micros),
+        }
+        last_level = Some(level);
+    }
+    decode(&pulses)
+}
+
+/// Decodes each of the given frames separately with the same options, lazily as the returned
+/// iterator is advanced.
+///
+/// Each frame should already have been split from the capture by the caller; it is decoded exactly
+/// as if it were passed to [`decode_with`] on its own.
+pub fn decode_frames<'a>(
+    frames: impl Iterator<Item = &'a [u16]> + 'a,
+    options: &'a DecodeOptions,
+) -> impl Iterator<Item = Result<Code, Error>> + 'a {
+    frames.map(move |frame| decode_with(frame, options))
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), decodes all
+/// frames and returns the code which was decoded most often, along with the number of frames which
+/// agreed on it.
+///
+/// If several codes were decoded equally often then the one seen first wins. If no frames could be
+/// decoded then the error from the first frame is returned, or [`Error::EmptyInput`] if there are
+/// no pulses.
+#[cfg(feature = "alloc")]
+pub fn best_code(pulses: &[u16]) -> Result<(Code, usize), Error> {
+    best_code_with(pulses, &DecodeOptions::default())
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), decodes all
+/// frames using the given options and returns the code which was decoded most often, along with the
+/// number of frames which agreed on it.
+///
+/// This is the same as [`best_code`] but with options, such as for a remote with an unusual break
+/// pulse length. Unlike [`recognize`], glitches aren't filtered and there is no minimum agreement.
+#[cfg(feature = "alloc")]
+pub fn best_code_with(pulses: &[u16], options: &DecodeOptions) -> Result<(Code, usize), Error> {
+    if pulses.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    vote(decode_all_with(pulses, options))
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), decodes all
+/// frames and returns each distinct code found along with the number of frames which it was decoded
+/// from, in the order they were first seen.
+///
+/// This is useful when several remotes may have been transmitting at the same time. Frames which
+/// fail to decode or have no bits are ignored.
+#[cfg(feature = "alloc")]
+pub fn distinct_codes(pulses: &[u16]) -> Vec<(Code, usize)> {
+    let (mut counts, _) = tally(decode_all(pulses));
+    counts.retain(|(code, _)| code.length > 0);
+    counts
+}
+
+/// Returns how similar a candidate code is to a reference code, from 0 to 1.
+///
+/// This is the fraction of bits which are the same, or 0 if the codes have different lengths. Codes
+/// with no bits match exactly if both are empty. Averaging this across several captures of the
+/// same button gives an idea of how reliably it is being received.
+pub fn match_ratio(reference: &Code, candidate: &Code) -> f32 {
+    match reference.hamming_distance(candidate) {
+        None => 0.0,
+        Some(_) if reference.length == 0 => 1.0,
+        Some(distance) => 1.0 - distance as f32 / f32::from(reference.length),
+    }
+}
+
+/// The minimum number of button presses for [`is_rolling`] to decide that a remote uses rolling
+/// codes.
+const MIN_ROLLING_PRESSES: usize = 3;
+
+/// Makes a best-effort guess at whether a remote sends rolling codes, given the codes it sent for
+/// several separate presses of the same button.
+///
+/// A remote with static codes sends the same code every time, so can be cloned by replaying it.
+/// A remote with rolling codes, such as KeeLoq, sends a different code every time. This returns
+/// true if there are at least 3 codes, they all have the same length, and no two are the same. A
+/// single press received with errors could make a static remote look like it uses rolling codes
+/// with only a few presses, so more is better.
+pub fn is_rolling(codes: &[Code]) -> bool {
+    codes.len() >= MIN_ROLLING_PRESSES
+        && codes.iter().all(|code| code.length == codes[0].length)
+        && codes
+            .iter()
+            .enumerate()
+            .all(|(i, code)| !codes[..i].contains(code))
+}
+
+/// Returns the code which appears most often in the given results, along with the number of times
+/// it appears, or the first error if there are no codes.
+#[cfg(feature = "alloc")]
+fn vote(results: Vec<Result<Code, Error>>) -> Result<(Code, usize), Error> {
+    let (counts, first_error) = tally(results);
+    counts
+        .into_iter()
+        .reduce(|best, entry| if entry.1 > best.1 { entry } else { best })
+        .ok_or_else(|| first_error.unwrap_or(Error::NoStart))
+}
+
+/// Counts how many times each distinct code appears in the given results, in the order they are
+/// first seen, and also returns the first error if any.
+#[cfg(feature = "alloc")]
+fn tally(results: Vec<Result<Code, Error>>) -> (Vec<(Code, usize)>, Option<Error>) {
+    let mut counts: Vec<(Code, usize)> = Vec::new();
+    let mut first_error = None;
+    for result in results {
+        match result {
+            Ok(code) => {
+                if let Some((_, count)) = counts.iter_mut().find(|(seen, _)| *seen == code) {
+                    *count += 1;
+                } else {
+                    counts.push((code, 1));
+                }
+            }
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    (counts, first_error)
+}
+
+/// Removes glitches from a sequence of pulse durations in microseconds, by merging each pulse
+/// shorter than `min_length` together with the pulse after it into the pulse before it.
+///
+/// A glitch at the very start of the sequence is kept, as there is nothing to merge it into.
+#[cfg(feature = "alloc")]
+pub fn filter_glitches(pulses: &[u16], min_length: u16) -> Vec<u16> {
+    let mut filtered: Vec<u16> = Vec::with_capacity(pulses.len());
+    let mut pulses = pulses.iter().copied();
+    while let Some(pulse) = pulses.next() {
+        match filtered.last_mut() {
+            Some(last) if pulse < min_length => {
+                // The pulse after the glitch has the same level as the one before it.
+                *last = last
+                    .saturating_add(pulse)
+                    .saturating_add(pulses.next().unwrap_or(0));
+            }
+            _ => filtered.push(pulse),
+        }
+    }
+    filtered
+}
+
+/// Compares two sequences of pulse durations, and returns the index and both durations of every
+/// pulse which differs between them by more than `tolerance_pct` percent of the longer of the two.
+///
+/// If one sequence is longer than the other then its extra pulses are all reported as differing,
+/// with a duration of 0 for the missing pulses.
+#[cfg(feature = "alloc")]
+pub fn diff_pulses(a: &[u16], b: &[u16], tolerance_pct: u8) -> Vec<(usize, u16, u16)> {
+    (0..a.len().max(b.len()))
+        .filter_map(|i| {
+            let (a, b) = (
+                a.get(i).copied().unwrap_or(0),
+                b.get(i).copied().unwrap_or(0),
+            );
+            let difference = u32::from(a.abs_diff(b)) * 100;
+            if difference > u32::from(tolerance_pct) * u32::from(a.max(b)) {
+                Some((i, a, b))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), recognises
+/// the button code which was sent.
+///
+/// This filters out glitches shorter than `options.glitch_length`, decodes every frame, and returns
+/// the code which most frames agree on. It fails with [`Error::NoAgreement`] if fewer than
+/// `options.min_agreement` frames agree on it, or [`Error::EmptyInput`] if there are no pulses.
+#[cfg(feature = "alloc")]
+pub fn recognize(pulses: &[u16], options: &DecodeOptions) -> Result<Code, Error> {
+    if pulses.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    let pulses = filter_glitches(pulses, options.glitch_length);
+    let (code, count) = vote(decode_all_with(&pulses, options))?;
+    if count < options.min_agreement {
+        return Err(Error::NoAgreement(count));
+    }
+    Ok(code)
+}
+
+/// A single press of a button, which may have sent several repeated frames.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Press {
+    /// The code which was sent.
+    pub code: Code,
+    /// The number of frames which were decoded for the press.
+    pub repeats: usize,
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), decodes all
+/// frames and groups them into separate button presses.
+///
+/// Remotes repeat their code for as long as a button is held, with a short break between frames.
+/// Consecutive frames with the same code are treated as the same press unless the break before
+/// the second is longer than `gap_threshold` microseconds. Frames which fail to decode or have no
+/// bits are ignored.
+#[cfg(feature = "alloc")]
+pub fn detect_presses(pulses: &[u16], gap_threshold: u16) -> Vec<Press> {
+    let options = DecodeOptions::default();
+    let mut presses: Vec<Press> = Vec::new();
+    let mut search_from = 0;
+    while let Some(start) =
+        next_start(pulses, search_from, &options, 1).filter(|&start| start < pulses.len())
+    {
+        search_from = start;
+        let Ok(Frame { code, next, .. }) =
+            decode_frame_scaled(&pulses[start..], start, &options, 1, &mut ())
+        else {
+            continue;
+        };
+        search_from += next;
+        // Skip false starts, such as a stray pulse before a long gap.
+        if code.length == 0 {
+            continue;
+        }
+        let gap = pulses[start - 1];
+        match presses.last_mut() {
+            Some(press) if press.code == code && gap <= gap_threshold => press.repeats += 1,
+            _ => presses.push(Press { code, repeats: 1 }),
+        }
+    }
+    presses
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), tries decoding
+/// the first frame with several different short pulse durations, and returns each distinct code
+/// found along with the short pulse duration which produced it.
+///
+/// This is useful for rescuing marginal captures which [`decode`] fails on because its estimate of
+/// the short pulse duration is wrong. The hypotheses are derived from clusters of similar pulse
+/// lengths within the frame, divided by each of the multiples used by the default symbols.
+#[cfg(feature = "alloc")]
+pub fn decode_candidates(pulses: &[u16]) -> Vec<(Code, u16)> {
+    let options = DecodeOptions::default();
+    let Some(start) = next_start(pulses, 0, &options, 1) else {
+        return Vec::new();
+    };
+    let frame = &pulses[start..];
+    let frame_end = frame
+        .iter()
+        .position(|&pulse| pulse > options.break_pulse_length)
+        .unwrap_or(frame.len());
+
+    let mut multiples = vec![options.one.0, options.one.1, options.zero.0, options.zero.1];
+    multiples.sort_unstable();
+    multiples.dedup();
+
+    let mut candidates: Vec<(Code, u16)> = Vec::new();
+    for cluster in pulse_clusters(&frame[..frame_end]) {
+        for &multiple in &multiples {
+            let short_duration = cluster.mean / u16::from(multiple);
+            if short_duration == 0 {
+                continue;
+            }
+            if let Ok(Frame { code, .. }) =
+                decode_symbols(frame, start, short_duration.into(), &options, 1, &mut ())
+            {
+                if code.length > 0 && !candidates.iter().any(|(seen, _)| *seen == code) {
+                    candidates.push((code, short_duration));
+                }
+            }
+        }
+    }
+    candidates
+}
+
+/// A group of pulses of similar lengths.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PulseCluster {
+    /// The mean length of the pulses in microseconds.
+    pub mean: u16,
+    /// The number of pulses in the cluster.
+    pub count: usize,
+}
+
+/// Groups the given pulse durations into clusters of similar lengths, in increasing order of
+/// length.
+///
+/// Each cluster contains pulses no more than 1.5 times as long as its shortest pulse.
+#[cfg(feature = "alloc")]
+fn pulse_clusters(pulses: &[u16]) -> Vec<PulseCluster> {
+    let mut sorted = pulses.to_vec();
+    sorted.sort_unstable();
+    let mut clusters = Vec::new();
+    let mut rest = sorted.as_slice();
+    while let Some(&shortest) = rest.first() {
+        let limit = u32::from(shortest) * 3 / 2;
+        let len = rest
+            .iter()
+            .position(|&pulse| u32::from(pulse) > limit)
+            .unwrap_or(rest.len());
+        let sum: u32 = rest[..len].iter().map(|&pulse| u32::from(pulse)).sum();
+        clusters.push(PulseCluster {
+            mean: (sum / len as u32) as u16,
+            count: len,
+        });
+        rest = &rest[len..];
+    }
+    clusters
+}
+
+/// The classification of a single pulse relative to the short pulse duration.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PulseClass {
+    /// A pulse of about the short pulse duration.
+    Short,
+    /// A pulse of about three times the short pulse duration.
+    Long,
+    /// A pulse longer than [`DEFAULT_BREAK_PULSE_LENGTH`].
+    Break,
+    /// Any other pulse.
+    Unknown,
+}
+
+/// Classifies a pulse duration in microseconds by rounding it to the nearest multiple of the short
+/// pulse duration, in the same way as the decoder does with the default options.
+///
+/// A pulse of twice the short pulse duration is `Unknown`, while a pulse of 2.5 times the short
+/// pulse duration rounds up to `Long`. If the short pulse duration is 0 then any pulse other than a
+/// break is `Unknown`.
+pub fn classify_pulse(pulse: u16, short_duration: u16) -> PulseClass {
+    if pulse > DEFAULT_BREAK_PULSE_LENGTH {
+        PulseClass::Break
+    } else {
+        match round_div(pulse.into(), short_duration.into()) {
+            1 => PulseClass::Short,
+            3 => PulseClass::Long,
+            _ => PulseClass::Unknown,
+        }
+    }
+}
+
+/// Divide one integer by another, rounding towards the closest integer.
+///
+/// This saturates rather than overflowing, and dividing by zero gives `u64::MAX`.
+fn round_div(dividend: u64, divisor: u64) -> u64 {
+    dividend
+        .saturating_add(divisor / 2)
+        .checked_div(divisor)
+        .unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use std::sync::Mutex;
+
+    /// A real capture of a doorbell button, with several repeated frames.
+    const DOORBELL: &[u16] = &[
+        320, 10060, 320, 960, 960, 300, 300, 960, 320, 960, 960, 300, 300, 960, 300, 980, 300, 960,
+        960, 300, 320, 960, 960, 300, 960, 320, 300, 960, 300, 960, 960, 320, 300, 960, 960, 320,
+        300, 960, 960, 320, 300, 960, 300, 960, 980, 300, 300, 960, 320, 960, 300, 10080, 320, 960,
+        960, 320, 300, 960, 300, 960, 980, 300, 300, 960, 320, 960, 300, 960, 960, 320, 300, 960,
+        960, 320, 960, 300, 300, 960, 320, 960, 960, 300, 320, 960, 960, 300, 320, 960, 960, 300,
+        320, 960, 300, 960, 960, 320, 300, 960, 320, 960, 300, 10080, 320, 960, 960, 320, 300, 960,
+        300, 960, 960, 320, 300, 960, 320, 960, 300, 960, 960, 320, 300, 960, 960, 320, 960, 300,
+        320, 960, 300, 960, 960, 320, 300, 960, 960, 320, 300, 960, 960, 320, 300, 960, 300, 960,
+        980, 300, 300, 960, 320, 960, 300, 10100, 300, 980, 960, 300, 300, 960, 320, 960, 960, 300,
+        320, 960, 300, 960, 300, 980, 960, 300, 320, 960, 960, 300, 960, 320, 300, 960, 320, 960,
+        960, 300, 320, 960, 960, 300, 320, 960, 960, 300, 320, 960, 300, 960, 960, 320, 300, 960,
+        300, 960, 320, 10100, 300, 960, 960, 320, 300, 960, 320, 940, 980, 300, 300, 980, 300, 960,
+        300, 960, 980, 300, 300, 960, 960, 320, 960, 320, 300, 960, 300, 960, 980, 300, 300, 960,
+        960, 320, 300, 960, 980, 300, 300, 960, 320, 960, 960, 300, 320, 960, 300, 960, 320, 10080,
+        320, 960, 960, 300, 320, 960, 300, 960, 960, 320, 300, 960, 320, 960, 300, 960, 960, 320,
+        300, 960, 960, 320, 960, 300, 320, 960, 300, 960, 960, 320, 300, 960, 960, 320, 300, 960,
+        960, 320, 300, 960, 320, 960, 960, 300, 320, 960, 300,
+    ];
+
+    #[test]
+    fn value_eq_ignores_length() {
+        let code = Code {
+            value: 0x4763a,
+            length: 20,
+        };
+        let longer = Code {
+            value: 0x4763a,
+            length: 24,
+        };
+        assert!(code.value_eq(&longer));
+        assert_ne!(code, longer);
+        assert!(!code.value_eq(&Code {
+            value: 0x4763b,
+            length: 20
+        }));
+    }
+
+    #[test]
+    fn new_validates() {
+        assert_eq!(
+            Code::new(0x4763a, 19),
+            Ok(Code {
+                value: 0x4763a,
+                length: 19
+            })
+        );
+        assert_eq!(
+            Code::try_from((0xffffffff, 32)),
+            Ok(Code {
+                value: 0xffffffff,
+                length: 32
+            })
+        );
+        assert_eq!(Code::new(0x8, 3), Err(Error::ValueOutOfRange(0x8, 3)));
+        assert_eq!(Code::try_from((0, 129)), Err(Error::TooLong));
+        assert_eq!(u128::from(Code::new(0x4763a, 19).unwrap()), 0x4763a);
+    }
+
+    #[test]
+    fn from_gray() {
+        let gray = [0b000, 0b001, 0b011, 0b010, 0b110, 0b111, 0b101, 0b100];
+        for (binary, gray) in gray.into_iter().enumerate() {
+            assert_eq!(
+                Code {
+                    value: gray,
+                    length: 3
+                }
+                .from_gray(),
+                Code {
+                    value: binary as u128,
+                    length: 3
+                }
+            );
+        }
+        assert_eq!(
+            Code {
+                value: 0x8000_0000,
+                length: 32
+            }
+            .from_gray(),
+            Code {
+                value: 0xffff_ffff,
+                length: 32
+            }
+        );
+    }
+
+    #[test]
+    fn truncated_to() {
+        let code = Code {
+            value: 0xffffff,
+            length: 24,
+        };
+        assert_eq!(
+            code.truncated_to(20),
+            Ok(Code {
+                value: 0x0fffff,
+                length: 20
+            })
+        );
+        assert_eq!(
+            code.truncated_to(32),
+            Ok(Code {
+                value: 0xffffff,
+                length: 32
+            })
+        );
+        assert_eq!(
+            code.truncated_to(0),
+            Ok(Code {
+                value: 0,
+                length: 0
+            })
+        );
+        assert_eq!(code.truncated_to(129), Err(Error::TooLong));
+    }
+
+    #[test]
+    fn trimmed_and_padded() {
+        let code = Code {
+            value: 0b0010,
+            length: 4,
+        };
+        assert_eq!(
+            code.trimmed(),
+            Code {
+                value: 0b10,
+                length: 2
+            }
+        );
+        assert_eq!(code.trimmed().padded_to(4), Ok(code));
+        assert_eq!(code.padded_to(1), Err(Error::ValueOutOfRange(0b10, 1)));
+        assert_eq!(
+            Code {
+                value: 0,
+                length: 8
+            }
+            .trimmed(),
+            Code {
+                value: 0,
+                length: 0
+            }
+        );
+    }
+
+    #[test]
+    fn hamming_distance() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        assert_eq!(code.hamming_distance(&code), Some(0));
+        assert_eq!(
+            code.hamming_distance(&Code {
+                value: 0x48b2a5,
+                length: 24
+            }),
+            Some(1)
+        );
+        assert_eq!(
+            code.hamming_distance(&Code {
+                value: 0x48b2a4,
+                length: 12
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn match_ratio_by_bits() {
+        let reference = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        assert_eq!(match_ratio(&reference, &reference), 1.0);
+        assert_eq!(
+            match_ratio(
+                &reference,
+                &Code {
+                    value: 0x48b2a7,
+                    length: 24
+                }
+            ),
+            1.0 - 2.0 / 24.0
+        );
+        assert_eq!(
+            match_ratio(
+                &reference,
+                &Code {
+                    value: !0x48b2a4 & 0xffffff,
+                    length: 24
+                }
+            ),
+            0.0
+        );
+        assert_eq!(
+            match_ratio(
+                &reference,
+                &Code {
+                    value: 0x48b2a4,
+                    length: 32
+                }
+            ),
+            0.0
+        );
+        let empty = Code {
+            value: 0,
+            length: 0,
+        };
+        assert_eq!(match_ratio(&empty, &empty), 1.0);
+    }
+
+    #[test]
+    fn rolling_codes() {
+        let code = |value| Code { value, length: 32 };
+        assert!(is_rolling(&[
+            code(0x12345678),
+            code(0x9abcdef0),
+            code(0x0fedcba9)
+        ]));
+        // Static codes repeat.
+        assert!(!is_rolling(&[code(0x48b2a4); 3]));
+        assert!(!is_rolling(&[
+            code(0x48b2a4),
+            code(0x48b2a5),
+            code(0x48b2a4)
+        ]));
+        // Too few presses to tell.
+        assert!(!is_rolling(&[code(0x12345678), code(0x9abcdef0)]));
+        // Different lengths are probably reception errors or different remotes.
+        assert!(!is_rolling(&[
+            code(0x12345678),
+            code(0x9abcdef0),
+            Code {
+                value: 0x48b2a4,
+                length: 24
+            }
+        ]));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn from_bits() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        assert_eq!(Code::from_bits(&code.bits()), Ok(code));
+        assert_eq!(
+            Code::from_bits(&[true, false, true, true]),
+            Ok(Code {
+                value: 0b1011,
+                length: 4
+            })
+        );
+        assert_eq!(
+            Code::from_bits(&[]),
+            Ok(Code {
+                value: 0,
+                length: 0
+            })
+        );
+        assert_eq!(Code::from_bits(&[true; 129]), Err(Error::TooLong));
+    }
+
+    #[test]
+    fn field() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        assert_eq!(code.field(0, 4), 0x4);
+        assert_eq!(code.field(4, 20), 0x48b2a);
+        assert_eq!(code.field(0, 24), 0x48b2a4);
+        assert_eq!(code.field(24, 0), 0);
+        assert_eq!(
+            Code {
+                value: 0xffffffff,
+                length: 32
+            }
+            .field(0, 32),
+            0xffffffff
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn field_out_of_bounds() {
+        Code {
+            value: 0x48b2a4,
+            length: 24,
+        }
+        .field(20, 5);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn to_hex_string() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        assert_eq!(code.to_hex_string(true, 7), "048B2A4");
+        assert_eq!(code.to_hex_string(false, 0), "48b2a4");
+        assert_eq!(
+            Code {
+                value: 0x5,
+                length: 13
+            }
+            .to_hex_string(true, 2),
+            "0005"
+        );
+    }
+
+    #[test]
+    fn format_radix() {
+        let code = Code {
+            value: 0b0010,
+            length: 4,
+        };
+        assert_eq!(format!("{:b}", code), "0010");
+        assert_eq!(format!("{:#b}", code), "0b0010");
+        assert_eq!(format!("{:x}", code), "2");
+        let code = Code {
+            value: 0xb2a4,
+            length: 24,
+        };
+        assert_eq!(format!("{:x}", code), "00b2a4");
+        assert_eq!(format!("{:#X}", code), "0x00B2A4");
+        assert_eq!(format!("{:08x}", code), "0000b2a4");
+        let code = Code {
+            value: u128::MAX,
+            length: 128,
+        };
+        assert_eq!(format!("{:b}", code), "1".repeat(128));
+        assert_eq!(format!("{:x}", code), "f".repeat(32));
+        let code = Code {
+            value: 0,
+            length: 0,
+        };
+        assert_eq!(format!("{:x}", code), "0");
+    }
+
+    #[test]
+    fn guess_protocol() {
+        let guess = |value, length| Code { value, length }.guess_protocol();
+        assert_eq!(guess(0xabc, 12), Some(ProtocolFamily::Pt2262));
+        assert_eq!(
+            guess(0b11_00_01_11_00_00_01_01_11_11_00_00, 24),
+            Some(ProtocolFamily::Pt2262)
+        );
+        assert_eq!(guess(0x48b2a4, 24), Some(ProtocolFamily::Ev1527));
+        assert_eq!(guess(0x48b2a4, 28), Some(ProtocolFamily::Ht6p20b));
+        assert_eq!(guess(0x48b2a4, 32), Some(ProtocolFamily::Keeloq));
+        assert_eq!(guess(0x48b2a4, 23), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn to_pulses() {
+        assert_eq!(
+            Code {
+                value: 0b10,
+                length: 2
+            }
+            .to_pulses(300),
+            vec![300, 9300, 900, 300, 300, 900, 300, 9300]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn duration_micros() {
+        for (code, short_duration) in [
+            (
+                Code {
+                    value: 0x48b2a4,
+                    length: 24,
+                },
+                320,
+            ),
+            (
+                Code {
+                    value: 0xffffffff,
+                    length: 32,
+                },
+                u16::MAX,
+            ),
+        ] {
+            assert_eq!(
+                code.duration_micros(short_duration),
+                code.to_pulses(short_duration)
+                    .into_iter()
+                    .map(u32::from)
+                    .sum::<u32>()
+            );
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn repeat_pulses_round_trip() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        assert_eq!(decode(&code.to_pulses(320)), Ok(code));
+        assert_eq!(
+            decode_all(&code.repeat_pulses(320, 5, 10000)),
+            vec![Ok(code); 5]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn encode_round_trip() {
+        for code in [
+            Code {
+                value: 0x48b2a4,
+                length: 24,
+            },
+            Code {
+                value: 0,
+                length: 12,
+            },
+            Code {
+                value: 0xffffffff,
+                length: 32,
+            },
+        ] {
+            let pulses = encode(&code);
+            assert_eq!(pulses, code.to_pulses(DEFAULT_SHORT_DURATION));
+            assert_eq!(decode(&pulses), Ok(code));
+        }
+    }
+
+    #[test]
+    fn classify_pulse_boundaries() {
+        assert_eq!(classify_pulse(159, 320), PulseClass::Unknown);
+        assert_eq!(classify_pulse(160, 320), PulseClass::Short);
+        assert_eq!(classify_pulse(320, 320), PulseClass::Short);
+        assert_eq!(classify_pulse(479, 320), PulseClass::Short);
+        assert_eq!(classify_pulse(480, 320), PulseClass::Unknown);
+        assert_eq!(classify_pulse(640, 320), PulseClass::Unknown);
+        assert_eq!(classify_pulse(800, 320), PulseClass::Long);
+        assert_eq!(classify_pulse(1119, 320), PulseClass::Long);
+        assert_eq!(classify_pulse(1120, 320), PulseClass::Unknown);
+        assert_eq!(classify_pulse(3000, 320), PulseClass::Unknown);
+        assert_eq!(classify_pulse(3001, 320), PulseClass::Break);
+        assert_eq!(classify_pulse(300, 0), PulseClass::Unknown);
+        assert_eq!(classify_pulse(u16::MAX, 1), PulseClass::Break);
+    }
+
+    /// Returns the bit which the given pair of pulses represents by rounding each pulse to a
+    /// multiple of the short pulse duration, as `SymbolClassifier` should without a tolerance.
+    fn classify_symbol(
+        high: u64,
+        low: u64,
+        short_duration: u64,
+        options: &DecodeOptions,
+    ) -> Option<bool> {
+        let periods = (
+            round_div(high, short_duration),
+            round_div(low, short_duration),
+        );
+        if periods == (options.one.0.into(), options.one.1.into()) {
+            Some(true)
+        } else if periods == (options.zero.0.into(), options.zero.1.into()) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    fn symbol_classifier_matches_classify_symbol() {
+        let options = [
+            DecodeOptions::default(),
+            DecodeOptions {
+                one: (2, 1),
+                zero: (1, 2),
+                ..Default::default()
+            },
+            DecodeOptions {
+                one: (0, 255),
+                zero: (255, 0),
+                ..Default::default()
+            },
+        ];
+        let durations = [
+            0,
+            1,
+            2,
+            3,
+            7,
+            300,
+            333,
+            u64::MAX / 300,
+            u64::MAX / 2,
+            u64::MAX,
+        ];
+        let pulses = (0..2000)
+            .chain(u64::MAX - 1000..=u64::MAX)
+            .chain(durations.iter().flat_map(|&duration| {
+                (0..=256).flat_map(move |multiple: u64| {
+                    let pulse = duration.saturating_mul(multiple);
+                    [pulse.saturating_sub(1), pulse, pulse.saturating_add(1)]
+                })
+            }))
+            .collect::<Vec<_>>();
+        for options in &options {
+            for short_duration in durations {
+                let classifier = SymbolClassifier::new((short_duration, short_duration), options);
+                for &high in &pulses {
+                    for low in [0, 1, short_duration, short_duration.saturating_mul(3)] {
+                        assert_eq!(
+                            classifier.classify(high, low),
+                            classify_symbol(high, low, short_duration, options),
+                            "{high} {low} {short_duration} {options:?}"
+                        );
+                        assert_eq!(
+                            classifier.classify(low, high),
+                            classify_symbol(low, high, short_duration, options),
+                            "{low} {high} {short_duration} {options:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_preamble() {
+        let preamble = Code {
+            value: 0b10101010,
+            length: 8,
+        };
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        // A 32 bit frame with the preamble followed by the code.
+        let pulses = Code {
+            value: preamble.value << 24 | code.value,
+            length: 32,
+        }
+        .to_pulses(320);
+        assert_eq!(
+            decode_with(
+                &pulses,
+                &DecodeOptions {
+                    preamble_bits: 8,
+                    expected_length: Some(24),
+                    ..Default::default()
+                }
+            ),
+            Ok(code)
+        );
+        // The preamble doesn't count towards the maximum length.
+        let long_code = Code {
+            value: 0xdeadbeef_01234567_89abcdef_deadbeef,
+            length: 128,
+        };
+        let pulses = [
+            &preamble.to_pulses(320)[..18],
+            &long_code.to_pulses(320)[2..],
+        ]
+        .concat();
+        assert_eq!(decode(&pulses), Err(Error::TooLong));
+        assert_eq!(
+            decode_with(
+                &pulses,
+                &DecodeOptions {
+                    preamble_bits: 8,
+                    ..Default::default()
+                }
+            ),
+            Ok(long_code)
+        );
+    }
+
+    #[test]
+    fn decode_separate_high_low() {
+        // Highs read 160 μs longer than they should and lows 160 μs shorter, for a short pulse
+        // duration of 300 μs.
+        let pulses = [
+            460, 9300, 1060, 140, 460, 740, 1060, 140, 460, 740, 460, 9300,
+        ];
+        assert_eq!(decode(&pulses), Err(Error::InvalidPulseLength(1060, 140)));
+        let options = DecodeOptions {
+            separate_high_low: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_with(&pulses, &options),
+            Ok(Code {
+                value: 0b1010,
+                length: 4
+            })
+        );
+        assert_eq!(
+            decode_with(
+                &pulses,
+                &DecodeOptions {
+                    validate_start: true,
+                    ..options
+                }
+            ),
+            Ok(Code {
+                value: 0b1010,
+                length: 4
+            })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_stretched_start() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        let mut pulses = code.to_pulses(320);
+        // Estimating from just the first two symbols would give 392 μs.
+        pulses[2..6].copy_from_slice(&[470, 1100, 1100, 470]);
+        assert_eq!(decode(&pulses), Ok(code));
+        assert_eq!(
+            short_durations(&pulses, &DecodeOptions::default()),
+            vec![Ok(326)]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_known_short_duration() {
+        // Most of the pulses are valid but short, so the short pulse duration estimated from the
+        // frame is too short to decode the symbol which isn't.
+        let pulses = [
+            320, 10000, 810, 170, 170, 810, 960, 320, 170, 810, 170, 10000,
+        ];
+        assert_eq!(decode(&pulses), Err(Error::InvalidPulseLength(960, 320)));
+        let options = DecodeOptions {
+            short_duration: Some(320),
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_with(&pulses, &options),
+            Ok(Code {
+                value: 0b1010,
+                length: 4
+            })
+        );
+        assert_eq!(
+            decode_detailed(&pulses, &options).unwrap().short_duration,
+            320
+        );
+        assert_eq!(
+            decode_nanos(
+                &pulses.map(|pulse| u32::from(pulse) * 1000),
+                &DecodeOptions {
+                    validate_start: true,
+                    ..options
+                }
+            ),
+            Ok(Code {
+                value: 0b1010,
+                length: 4
+            })
+        );
+        assert_eq!(
+            decode_with(
+                &pulses,
+                &DecodeOptions {
+                    short_duration: Some(0),
+                    ..Default::default()
+                }
+            ),
+            Err(Error::NoSignal)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_nearest_symbol() {
+        // The third symbol is closer to a 1 than a 0, but doesn't round to either.
+        let pulses = [
+            300, 10000, 900, 300, 300, 900, 750, 450, 900, 300, 300, 10000,
+        ];
+        assert_eq!(decode(&pulses), Err(Error::InvalidPulseLength(750, 450)));
+        assert_eq!(
+            decode_with(
+                &pulses,
+                &DecodeOptions {
+                    tolerance: Some(30),
+                    ..Default::default()
+                }
+            ),
+            Ok(Code {
+                value: 0b1011,
+                length: 4
+            })
+        );
+        assert_eq!(
+            decode_with(
+                &pulses,
+                &DecodeOptions {
+                    tolerance: Some(20),
+                    ..Default::default()
+                }
+            ),
+            Err(Error::InvalidPulseLength(750, 450))
+        );
+        // Clean captures decode the same either way.
+        assert_eq!(
+            decode_all_with(
+                DOORBELL,
+                &DecodeOptions {
+                    tolerance: Some(30),
+                    ..Default::default()
+                }
+            ),
+            decode_all(DOORBELL)
+        );
+    }
+
+    #[test]
+    fn decode_startup_tolerance() {
+        // The first symbol is closer to a 1 than a 0, but doesn't round to either.
+        let noisy_start = [
+            300, 10000, 750, 450, 900, 300, 300, 900, 900, 300, 300, 10000,
+        ];
+        let noisy_end = [
+            300, 10000, 900, 300, 900, 300, 300, 900, 750, 450, 300, 10000,
+        ];
+        let options = DecodeOptions {
+            short_duration: Some(300),
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_with(&noisy_start, &options),
+            Err(Error::InvalidPulseLength(750, 450))
+        );
+        let options = DecodeOptions {
+            startup_tolerance: Some(30),
+            startup_symbols: 2,
+            ..options
+        };
+        assert_eq!(
+            decode_with(&noisy_start, &options),
+            Ok(Code {
+                value: 0b1101,
+                length: 4
+            })
+        );
+        assert_eq!(
+            decode_with(&noisy_end, &options),
+            Err(Error::InvalidPulseLength(750, 450))
+        );
+    }
+
+    #[test]
+    fn round_div_saturates() {
+        assert_eq!(round_div(5, 2), 3);
+        assert_eq!(round_div(u64::MAX, 2), u64::MAX / 2);
+        assert_eq!(round_div(1, 0), u64::MAX);
+    }
+
+    #[test]
+    fn decode_no_start() {
+        assert_eq!(decode(&[]), Err(Error::EmptyInput));
+        assert_eq!(decode(&[300, 1000, 300, 1000]), Err(Error::NoStart));
+    }
+
+    #[test]
+    fn decode_no_signal() {
+        assert_eq!(decode(&[300, 10000, 1, 1, 1, 1]), Err(Error::NoSignal));
+    }
+
+    #[test]
+    fn decode_short() {
+        assert_eq!(
+            decode(&[300, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333]),
+            Ok(Code {
+                value: 0b1101,
+                length: 4
+            })
+        );
+    }
+
+    #[test]
+    fn try_from_pulses() {
+        let pulses: &[u16] = &[300, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333];
+        assert_eq!(
+            Code::try_from(pulses),
+            Ok(Code {
+                value: 0b1101,
+                length: 4
+            })
+        );
+        assert_eq!(Code::try_from(&[][..]), Err(Error::EmptyInput));
+    }
+
+    #[test]
+    fn decode_short_repeated() {
+        assert_eq!(
+            decode(&[
+                300, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 10000, 1000, 333
+            ]),
+            Ok(Code {
+                value: 0b1101,
+                length: 4
+            })
+        );
+    }
+
+    #[test]
+    fn decode_with_ratio() {
+        let options = DecodeOptions {
+            one: (2, 1),
+            zero: (1, 2),
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_with(
+                &[300, 10000, 600, 300, 300, 600, 600, 300, 600, 300, 300, 10000],
+                &options
+            ),
+            Ok(Code {
+                value: 0b1011,
+                length: 4
+            })
+        );
+    }
+
+    #[test]
+    fn decode_with_zero_multiples() {
+        let options = DecodeOptions {
+            one: (0, 0),
+            zero: (0, 0),
+            ..Default::default()
+        };
+        let pulses = [300, 10000, 900, 300, 300, 900, 300, 10000];
+        assert_eq!(first_symbols_short_duration(&pulses[2..], &options), None);
+        assert_eq!(estimate_short_duration(&pulses[2..], &options, 1), None);
+        assert!(decode_with(&pulses, &options).is_err());
+    }
+
+    #[test]
+    fn decode_with_break_pulse_length() {
+        let pulses = [
+            300, 2000, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 2000,
+        ];
+        assert_eq!(decode(&pulses), Err(Error::NoStart));
+        assert_eq!(
+            decode_with(
+                &pulses,
+                &DecodeOptions {
+                    break_pulse_length: 1500,
+                    ..Default::default()
+                }
+            ),
+            Ok(Code {
+                value: 0b1101,
+                length: 4
+            })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_with_break_multiple() {
+        let mut pulses = Code {
+            value: 0b1011,
+            length: 4,
+        }
+        .to_pulses(1500);
+        // The long data pulses are longer than the break pulse length, so a corrupt symbol looks
+        // like the end of the frame.
+        pulses[6] = 4500;
+        pulses[7] = 4500;
+        assert_eq!(
+            decode(&pulses),
+            Ok(Code {
+                value: 0b10,
+                length: 2
+            })
+        );
+
+        let options = DecodeOptions {
+            break_multiple: Some(8),
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_with(&pulses, &options),
+            Err(Error::InvalidPulseLength(4500, 4500))
+        );
+        assert_eq!(
+            decode_with(
+                &Code {
+                    value: 0b1011,
+                    length: 4
+                }
+                .to_pulses(1500),
+                &options
+            ),
+            Ok(Code {
+                value: 0b1011,
+                length: 4
+            })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_lsb_first() {
+        let pulses = Code {
+            value: 0b1011_0001,
+            length: 8,
+        }
+        .to_pulses(320);
+        let options = DecodeOptions {
+            bit_order: BitOrder::LsbFirst,
+            ..Default::default()
+        };
+        let code = Code {
+            value: 0b1000_1101,
+            length: 8,
+        };
+        assert_eq!(decode_with(&pulses, &options), Ok(code));
+        assert_eq!(decode(&code.reversed().to_pulses(320)), Ok(code.reversed()));
+
+        // The sync word is matched as received, before the rest is reversed.
+        let options = DecodeOptions {
+            sync_word: Some((0b10, 2)),
+            ..options
+        };
+        let pulses = Code {
+            value: 0b10_110,
+            length: 5,
+        }
+        .to_pulses(320);
+        assert_eq!(
+            decode_with(&pulses, &options),
+            Ok(Code {
+                value: 0b011,
+                length: 3
+            })
+        );
+    }
+
+    #[test]
+    fn reversed() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        assert_eq!(
+            code.reversed(),
+            Code {
+                value: 0x254d12,
+                length: 24
+            }
+        );
+        assert_eq!(code.reversed().reversed(), code);
+        assert_eq!(
+            Code {
+                value: 0,
+                length: 0
+            }
+            .reversed(),
+            Code {
+                value: 0,
+                length: 0
+            }
+        );
+        assert_eq!(
+            Code {
+                value: 1,
+                length: 128
+            }
+            .reversed()
+            .value,
+            1 << 127
+        );
+    }
+
+    #[test]
+    fn decode_with_parity() {
+        let options = DecodeOptions {
+            checksum: Some(Checksum::EvenParity),
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_with(
+                &[300, 10000, 1000, 333, 1000, 333, 333, 1000, 333, 1000, 333, 10000],
+                &options
+            ),
+            Ok(Code {
+                value: 0b1100,
+                length: 4
+            })
+        );
+        assert_eq!(
+            decode_with(
+                &[300, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 10000],
+                &options
+            ),
+            Err(Error::ChecksumFailed)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_with_ambiguity() {
+        let mut pulses = Code {
+            value: 0b1011,
+            length: 4,
+        }
+        .to_pulses(300);
+        // Make the third bit ambiguous.
+        pulses[6] = 600;
+        pulses[7] = 600;
+        let with_policy = |ambiguity| {
+            decode_with_uncertainty(
+                &pulses,
+                &DecodeOptions {
+                    ambiguity,
+                    ..Default::default()
+                },
+            )
+        };
+        assert_eq!(
+            with_policy(AmbiguityPolicy::Abort),
+            Err(Error::InvalidPulseLength(600, 600))
+        );
+        // Aborting treats the symbol like any other invalid symbol, so it can still be resynced.
+        assert_eq!(
+            decode_with_uncertainty(
+                &pulses,
+                &DecodeOptions {
+                    resync_on_error: true,
+                    ..Default::default()
+                },
+            ),
+            Ok((
+                Code {
+                    value: 0b1001,
+                    length: 4
+                },
+                vec![2]
+            ))
+        );
+        assert_eq!(
+            with_policy(AmbiguityPolicy::Skip),
+            Ok((
+                Code {
+                    value: 0b101,
+                    length: 3
+                },
+                vec![]
+            ))
+        );
+        assert_eq!(
+            with_policy(AmbiguityPolicy::Majority),
+            Ok((
+                Code {
+                    value: 0b1001,
+                    length: 4
+                },
+                vec![2]
+            ))
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_detailed_frame_end() {
+        let code = Code {
+            value: 0b1011,
+            length: 4,
+        };
+        let pulses = code.to_pulses(300);
+        let end = |pulses: &[u16]| {
+            decode_detailed(pulses, &DecodeOptions::default())
+                .map(|details| (details.code, details.end))
+        };
+        assert_eq!(end(&pulses), Ok((code, FrameEnd::Break)));
+        assert_eq!(end(&pulses[..10]), Ok((code, FrameEnd::Complete)));
+        assert_eq!(
+            end(&pulses[..9]),
+            Ok((
+                Code {
+                    value: 0b101,
+                    length: 3
+                },
+                FrameEnd::Truncated
+            ))
+        );
+        assert_eq!(
+            decode_detailed(
+                &pulses,
+                &DecodeOptions {
+                    expected_length: Some(2),
+                    ..Default::default()
+                }
+            )
+            .map(|details| details.end),
+            Ok(FrameEnd::ExpectedLength)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_sync_word() {
+        let options = DecodeOptions {
+            sync_word: Some((0b1010, 4)),
+            ..Default::default()
+        };
+        let decode_code = |value, length, options: &DecodeOptions| {
+            decode_with(&Code { value, length }.to_pulses(300), options)
+        };
+        assert_eq!(
+            decode_code(0b1010_0110, 8, &options),
+            Ok(Code {
+                value: 0b0110,
+                length: 4
+            })
+        );
+        assert_eq!(
+            decode_code(0b1011_0110, 8, &options),
+            Err(Error::SyncMismatch)
+        );
+        assert_eq!(decode_code(0b101, 3, &options), Err(Error::SyncMismatch));
+        assert_eq!(
+            decode_code(0b1010, 4, &options),
+            Ok(Code {
+                value: 0,
+                length: 0
+            })
+        );
+        // The expected length and checksum are for the bits after the sync word.
+        assert_eq!(
+            decode_code(
+                0b1_0110_1101,
+                9,
+                &DecodeOptions {
+                    sync_word: Some((0b1011, 4)),
+                    expected_length: Some(4),
+                    checksum: Some(Checksum::EvenParity),
+                    ..options
+                }
+            ),
+            Ok(Code {
+                value: 0b0110,
+                length: 4
+            })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn require_trailing_break() {
+        let code = Code {
+            value: 0b1011,
+            length: 4,
+        };
+        let pulses = code.to_pulses(300);
+        let options = DecodeOptions {
+            require_trailing_break: true,
+            ..Default::default()
+        };
+        assert_eq!(decode(&pulses[..10]), Ok(code));
+        assert_eq!(decode_with(&pulses[..10], &options), Err(Error::NoEnd));
+        assert_eq!(decode_with(&pulses[..9], &options), Err(Error::NoEnd));
+        assert_eq!(decode_with(&pulses, &options), Ok(code));
+        assert_eq!(
+            decode_with(
+                &pulses[..8],
+                &DecodeOptions {
+                    expected_length: Some(2),
+                    ..options
+                }
+            ),
+            Ok(Code {
+                value: 0b10,
+                length: 2
+            })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_with_resync() {
+        let mut pulses = Code {
+            value: 0b1011,
+            length: 4,
+        }
+        .to_pulses(300);
+        // Corrupt the third bit.
+        pulses[6] = 600;
+        pulses[7] = 600;
+        assert_eq!(decode(&pulses), Err(Error::InvalidPulseLength(600, 600)));
+
+        let options = DecodeOptions {
+            resync_on_error: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_with_uncertainty(&pulses, &options),
+            Ok((
+                Code {
+                    value: 0b1001,
+                    length: 4
+                },
+                vec![2]
+            ))
+        );
+        assert_eq!(
+            decode_with(
+                &pulses,
+                &DecodeOptions {
+                    resync_placeholder: true,
+                    ..options
+                }
+            ),
+            Ok(Code {
+                value: 0b1011,
+                length: 4
+            })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_nanos_matches_micros() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        let pulses: Vec<u32> = code
+            .to_pulses(320)
+            .into_iter()
+            .map(|pulse| u32::from(pulse) * 1000)
+            .collect();
+        assert_eq!(decode_nanos(&pulses, &DecodeOptions::default()), Ok(code));
+        assert_eq!(
+            decode_nanos(
+                &[300_000, 10_000_000, 999_900, 333_300, 333_300, 999_900, 666_600, 666_600],
+                &DecodeOptions::default()
+            ),
+            Err(Error::InvalidPulseLength(666, 666))
+        );
+    }
+
+    #[test]
+    fn decode_with_min_bits() {
+        let pulses = [
+            300, 10000, 1000, 333, 333, 1000, 300, 10000, 1000, 333, 1000, 333, 333, 1000, 1000,
+            333, 333, 10000,
+        ];
+        assert_eq!(
+            decode(&pulses),
+            Ok(Code {
+                value: 0b10,
+                length: 2
+            })
+        );
+        let options = DecodeOptions {
+            min_bits: 4,
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_with(&pulses, &options),
+            Ok(Code {
+                value: 0b1101,
+                length: 4
+            })
+        );
+        assert_eq!(decode_with(&pulses[..8], &options), Err(Error::TooShort));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_with_min_bits_skips_invalid_frame() {
+        let code = Code {
+            value: 0xa5,
+            length: 8,
+        };
+        let mut pulses = vec![300, 9300, 900, 300, 5000, 5000];
+        pulses.extend(code.to_pulses(300));
+        assert_eq!(decode(&pulses), Err(Error::InvalidPulseLength(900, 300)));
+        let options = DecodeOptions {
+            min_bits: 8,
+            ..Default::default()
+        };
+        assert_eq!(decode_with(&pulses, &options), Ok(code));
+        assert_eq!(
+            decode_with(&pulses[..6], &options),
+            Err(Error::InvalidPulseLength(900, 300))
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_with_expected_length() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        let mut pulses = code.to_pulses(300);
+        // Add trailing noise which happens to look like a valid bit before the final break.
+        let last = pulses.len() - 2;
+        pulses.splice(last..last, [900, 300]);
+        assert_eq!(
+            decode(&pulses),
+            Ok(Code {
+                value: 0x48b2a4 << 1 | 1,
+                length: 25
+            })
+        );
+
+        let options = DecodeOptions {
+            expected_length: Some(24),
+            ..Default::default()
+        };
+        assert_eq!(decode_with(&pulses, &options), Ok(code));
+        assert_eq!(
+            decode_with(
+                &Code {
+                    value: 0x48b2a,
+                    length: 20
+                }
+                .to_pulses(300),
+                &options
+            ),
+            Err(Error::TooShort)
+        );
+    }
+
+    #[test]
+    fn decode_with_sync_pair() {
+        let options = DecodeOptions {
+            start: Start::SyncPair(1, 31),
+            ..Default::default()
+        };
+        // A long high pulse which isn't a sync, then HT12E style sync pairs.
+        let pulses = [
+            10000, 300, 1000, 50, 320, 9920, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 320, 9920,
+        ];
+        assert_eq!(decode(&pulses), Err(Error::InvalidPulseLength(300, 1000)));
+        assert_eq!(
+            decode_with(&pulses, &options),
+            Ok(Code {
+                value: 0b1101,
+                length: 4
+            })
+        );
+        assert_eq!(decode_with(&pulses[..4], &options), Err(Error::NoStart));
+        assert_eq!(
+            decode_with(
+                &[300, 10000, 300, 900],
+                &DecodeOptions {
+                    start: Start::SyncPair(0, 0),
+                    ..Default::default()
+                }
+            ),
+            Err(Error::NoStart)
+        );
+    }
+
+    #[test]
+    fn decode_with_validate_start() {
+        // A noise spike which looks like a break, followed by noise, then a real frame.
+        let pulses = [
+            300, 5000, 200, 400, 150, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 10000,
+        ];
+        assert_eq!(decode(&pulses), Err(Error::InvalidPulseLength(200, 400)));
+        assert_eq!(
+            decode_with(
+                &pulses,
+                &DecodeOptions {
+                    validate_start: true,
+                    ..Default::default()
+                }
+            ),
+            Ok(Code {
+                value: 0b1101,
+                length: 4
+            })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_all_repeated() {
+        assert_eq!(
+            decode_all(&[
+                300, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 10000, 1000, 333
+            ]),
+            vec![
+                Ok(Code {
+                    value: 0b1101,
+                    length: 4
+                }),
+                Err(Error::TooShort)
+            ]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn quality_trend_declines() {
+        let code = Code {
+            value: 0b1011,
+            length: 4,
+        };
+        let mut pulses = code.repeat_pulses(300, 3, 9300);
+        // Add increasing jitter to the second and third frames.
+        pulses[12] += 60;
+        pulses[23] -= 60;
+        pulses[24] += 90;
+        let scores = quality_trend(&pulses);
+        assert_eq!(scores.len(), 3);
+        assert_eq!(scores[0], 1.0);
+        assert!(scores[1] < scores[0]);
+        assert!(scores[2] < scores[1]);
+        assert_eq!(quality_trend(&[300, 9300, 1, 1, 1, 1]), vec![0.0]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_edges_merges_levels() {
+        let edges = [
+            (true, 300),
+            (false, 10000),
+            (true, 1000),
+            (false, 333),
+            (true, 400),
+            (true, 600),
+            (false, 333),
+            (true, 333),
+            (false, 1000),
+            (true, 1000),
+            (false, 333),
+            (true, 333),
+            (false, 10000),
+        ]
+        .map(|(level, micros)| (level, Duration::from_micros(micros)));
+        assert_eq!(
+            decode_edges(&edges),
+            Ok(Code {
+                value: 0b1101,
+                length: 4
+            })
+        );
+    }
+
+    #[test]
+    fn decode_frames_lazily() {
+        let frames: [&[u16]; 3] = [
+            &[300, 10000, 1000, 333, 333, 1000, 300, 10000],
+            &[1000, 333],
+            &[300, 10000, 1000, 333, 1000, 333, 300, 10000],
+        ];
+        assert_eq!(
+            decode_frames(frames.into_iter(), &DecodeOptions::default()).collect::<Vec<_>>(),
+            vec![
+                Ok(Code {
+                    value: 0b10,
+                    length: 2
+                }),
+                Err(Error::NoStart),
+                Ok(Code {
+                    value: 0b11,
+                    length: 2
+                }),
+            ]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn best_code_majority() {
+        assert_eq!(
+            best_code(&[
+                300, 10000, 1000, 333, 333, 1000, 300, 10000, 1000, 333, 1000, 333, 300, 10000,
+                1000, 333, 333, 1000, 300, 10000
+            ]),
+            Ok((
+                Code {
+                    value: 0b10,
+                    length: 2
+                },
+                2
+            ))
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn best_code_tie() {
+        assert_eq!(
+            best_code(&[
+                300, 10000, 1000, 333, 1000, 333, 300, 10000, 1000, 333, 333, 1000, 300, 10000
+            ]),
+            Ok((
+                Code {
+                    value: 0b11,
+                    length: 2
+                },
+                1
+            ))
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn distinct_codes_interleaved() {
+        let doorbell = Code {
+            value: 0b1011,
+            length: 4,
+        };
+        let garage = Code {
+            value: 0b0100,
+            length: 4,
+        };
+        let mut pulses = doorbell.repeat_pulses(300, 2, 9300);
+        pulses.extend(garage.repeat_pulses(300, 1, 9300));
+        pulses.extend(doorbell.repeat_pulses(300, 1, 9300));
+        pulses.extend(garage.repeat_pulses(300, 2, 9300));
+        assert_eq!(distinct_codes(&pulses), vec![(doorbell, 3), (garage, 3)]);
+        assert_eq!(distinct_codes(&[]), vec![]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn best_code_with_options() {
+        // The breaks are shorter than the default break pulse length, and the second frame is
+        // corrupted.
+        let pulses = [
+            100, 2000, 300, 100, 100, 300, 300, 100, 300, 100, 100, 2000, 300, 100, 100, 300, 200,
+            200, 300, 100, 100, 2000, 300, 100, 100, 300, 300, 100, 300, 100, 100, 2000,
+        ];
+        assert_eq!(best_code(&pulses), Err(Error::NoStart));
+        assert_eq!(
+            best_code_with(
+                &pulses,
+                &DecodeOptions {
+                    break_pulse_length: 1000,
+                    ..Default::default()
+                }
+            ),
+            Ok((
+                Code {
+                    value: 0b1011,
+                    length: 4
+                },
+                2
+            ))
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn best_code_no_frames() {
+        assert_eq!(best_code(&[300, 1000, 300]), Err(Error::NoStart));
+        assert_eq!(best_code(&[300, 10000, 1000, 333]), Err(Error::TooShort));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn vote_empty() {
+        let options = DecodeOptions::default();
+        assert_eq!(best_code(&[]), Err(Error::EmptyInput));
+        assert_eq!(best_code_with(&[], &options), Err(Error::EmptyInput));
+        assert_eq!(recognize(&[], &options), Err(Error::EmptyInput));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_long_codes() {
+        for code in [
+            Code {
+                value: 0x12_3456_789a,
+                length: 40,
+            },
+            Code {
+                value: 0x80_1234_5678_9abc,
+                length: 56,
+            },
+        ] {
+            let pulses = code.repeat_pulses(320, 3, 9920);
+            assert_eq!(decode(&pulses), Ok(code));
+            assert_eq!(decode_all(&pulses), vec![Ok(code); 3]);
+        }
+    }
+
+    #[test]
+    fn decode_too_long() {
+        let mut pulses = vec![300, 9300];
+        for _ in 0..136 {
+            pulses.extend([900, 300]);
+        }
+        pulses.extend([300, 9300]);
+        assert_eq!(decode(&pulses), Err(Error::TooLong));
+
+        pulses.drain(2..18);
+        assert_eq!(
+            decode(&pulses),
+            Ok(Code {
+                value: u128::MAX,
+                length: 128
+            })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_candidates_rescues_stretched_start() {
+        // The first four symbols are stretched, so the short pulse duration estimated from the frame
+        // is too long to decode the rest of it.
+        let pulses = [
+            300, 9300, 1100, 440, 440, 1100, 1100, 440, 440, 1100, 900, 300, 300, 9300,
+        ];
+        assert_eq!(decode(&pulses), Err(Error::InvalidPulseLength(900, 300)));
+        let candidates = decode_candidates(&pulses);
+        assert_eq!(
+            candidates.first(),
+            Some(&(
+                Code {
+                    value: 0b10101,
+                    length: 5
+                },
+                353
+            ))
+        );
+        assert_eq!(decode_candidates(&[300, 900]), vec![]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn detect_presses_by_gap() {
+        let doorbell = Code {
+            value: 0b1011,
+            length: 4,
+        };
+        let garage = Code {
+            value: 0b0100,
+            length: 4,
+        };
+        let mut pulses = doorbell.repeat_pulses(300, 3, 9300);
+        pulses.extend(doorbell.repeat_pulses(300, 2, 9300));
+        *pulses.last_mut().unwrap() = 60000;
+        pulses.extend(garage.repeat_pulses(300, 1, 9300));
+        // Lengthen the gap before the second press.
+        let index = 2 + 3 * 10;
+        assert_eq!(pulses[index..index + 2], [300, 9300]);
+        pulses[index + 1] = 50000;
+
+        assert_eq!(
+            detect_presses(&pulses, 20000),
+            vec![
+                Press {
+                    code: doorbell,
+                    repeats: 3
+                },
+                Press {
+                    code: doorbell,
+                    repeats: 2
+                },
+                Press {
+                    code: garage,
+                    repeats: 1
+                },
+            ]
+        );
+        assert_eq!(
+            detect_presses(&pulses, u16::MAX),
+            vec![
+                Press {
+                    code: doorbell,
+                    repeats: 5
+                },
+                Press {
+                    code: garage,
+                    repeats: 1
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn diff_pulses_tolerance() {
+        let good = [300, 9300, 900, 300, 300, 900];
+        let bad = [310, 9300, 600, 300, 300, 900, 300];
+        assert_eq!(
+            diff_pulses(&good, &bad, 10),
+            vec![(2, 900, 600), (6, 0, 300)]
+        );
+        assert_eq!(diff_pulses(&good, &bad, 0)[0], (0, 300, 310));
+        assert_eq!(diff_pulses(&good, &good, 0), vec![]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn filter_glitches_merges() {
+        assert_eq!(
+            filter_glitches(&[20, 300, 10, 600, 900, 300], 50),
+            vec![20, 910, 900, 300]
+        );
+        assert_eq!(filter_glitches(&[300, 900, 10], 50), vec![300, 910]);
+        assert_eq!(filter_glitches(&[300, 900, 10], 0), vec![300, 900, 10]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn recognize_glitchy_repeats() {
+        let code = Code {
+            value: 0b1011,
+            length: 4,
+        };
+        let mut pulses = code.repeat_pulses(300, 3, 9300);
+        // Split the long high pulse of the first 1 bit of the second frame with a glitch.
+        pulses.splice(12..13, [400, 20, 480]);
+        let options = DecodeOptions {
+            glitch_length: 50,
+            min_agreement: 3,
+            ..Default::default()
+        };
+        assert_eq!(recognize(&pulses, &options), Ok(code));
+        assert_eq!(
+            recognize(
+                &pulses,
+                &DecodeOptions {
+                    min_agreement: 4,
+                    ..options
+                }
+            ),
+            Err(Error::NoAgreement(3))
+        );
+    }
+
+    #[test]
+    fn decode_full() {
+        let decoded = decode(DOORBELL);
+        assert_eq!(
+            decoded,
+            Ok(Code {
+                value: 0x48b2a4,
+                length: 24
+            })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_detailed_pulses() {
+        let details = decode_detailed(DOORBELL, &DecodeOptions::default()).unwrap();
+        assert_eq!(details.pulses, &DOORBELL[1..52]);
+        assert_eq!(decode(&details.pulses), Ok(details.code));
+
+        let options = DecodeOptions {
+            start: Start::SyncPair(1, 31),
+            pair_offset: 1,
+            min_bits: 4,
+            ..Default::default()
+        };
+        let pulses = [
+            320, 9920, 333, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 9920,
+        ];
+        let details = decode_detailed(&pulses, &options).unwrap();
+        assert_eq!(
+            details.code,
+            Code {
+                value: 0b1101,
+                length: 4
+            }
+        );
+        assert_eq!(details.pulses, &pulses);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn auto_polarity() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        let options = DecodeOptions {
+            start: Start::SyncPair(1, 31),
+            ..Default::default()
+        };
+        let pulses = code.repeat_pulses(320, 3, 9920);
+        assert_eq!(
+            decode_auto_polarity(&pulses, &options),
+            Ok((code, Polarity::Normal))
+        );
+
+        // An extra pulse at the start makes every high pulse odd, as if the receiver was inverted.
+        let inverted = [&[2000], pulses.as_slice()].concat();
+        assert_eq!(decode_with(&inverted, &options), Err(Error::NoStart));
+        assert_eq!(
+            decode_auto_polarity(&inverted, &options),
+            Ok((code, Polarity::Inverted))
+        );
+
+        assert_eq!(decode_auto_polarity(&[], &options), Err(Error::EmptyInput));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_verbose_diagnostics() {
+        let pulses = [
+            300, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 10000,
+        ];
+        let (result, diagnostics) = decode_verbose(&pulses, &DecodeOptions::default());
+        assert_eq!(
+            result,
+            Ok(Code {
+                value: 0b1101,
+                length: 4
+            })
+        );
+        assert_eq!(diagnostics.start, Some(2));
+        assert_eq!(diagnostics.short_duration, Some(333));
+        assert_eq!(
+            diagnostics.symbols[0],
+            SymbolDiagnostics {
+                high: 1000,
+                low: 333,
+                bit: Some(true),
+                error: 1
+            }
+        );
+        assert_eq!(diagnostics.symbols.len(), 4);
+        assert_eq!(diagnostics.end, Some(FrameEnd::Break));
+        assert!(diagnostics.quality > 0.9);
+
+        // Diagnostics are still returned on failure, up to the invalid symbol.
+        let pulses = [
+            300, 10000, 1000, 333, 1000, 333, 350, 350, 1000, 333, 300, 10000,
+        ];
+        let (result, diagnostics) = decode_verbose(&pulses, &DecodeOptions::default());
+        assert_eq!(result, Err(Error::InvalidPulseLength(350, 350)));
+        assert_eq!(diagnostics.start, Some(2));
+        assert_eq!(diagnostics.short_duration, Some(333));
+        assert_eq!(
+            diagnostics.symbols.last(),
+            Some(&SymbolDiagnostics {
+                high: 350,
+                low: 350,
+                bit: None,
+                error: 666
+            })
+        );
+        assert_eq!(diagnostics.symbols.len(), 3);
+        assert_eq!(diagnostics.end, None);
+        assert_eq!(diagnostics.quality, 0.0);
+
+        let (result, diagnostics) = decode_verbose(&[300, 1000], &DecodeOptions::default());
+        assert_eq!(result, Err(Error::NoStart));
+        assert_eq!(diagnostics, Diagnostics::default());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_assume_start() {
+        let options = DecodeOptions {
+            assume_start: true,
+            ..Default::default()
+        };
+        let code = Code {
+            value: 0b1101,
+            length: 4,
+        };
+        let pulses = [1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 10000];
+        assert_eq!(decode(&pulses), Err(Error::TooShort));
+        assert_eq!(decode_with(&pulses, &options), Ok(code));
+        assert_eq!(decode_detailed(&pulses, &options).unwrap().pulses, &pulses);
+        // Pulses which start with a break are decoded as usual.
+        assert_eq!(
+            decode_with(
+                &[10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333],
+                &options
+            ),
+            Ok(code)
+        );
+        assert_eq!(decode_all_with(DOORBELL, &options), decode_all(DOORBELL));
+        // An assumed frame which is too short is skipped.
+        assert_eq!(
+            decode_with(
+                &[333, 1000, 1000, 333, 333, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333],
+                &DecodeOptions {
+                    min_bits: 4,
+                    ..options.clone()
+                }
+            ),
+            Ok(code)
+        );
+        assert_eq!(
+            decode_with(
+                &[1000, 333, 1000, 333],
+                &DecodeOptions {
+                    start: Start::SyncPair(1, 31),
+                    min_bits: 4,
+                    ..options.clone()
+                }
+            ),
+            Err(Error::TooShort)
+        );
+        assert_eq!(
+            decode_all_with(&[1000, 333], &options),
+            vec![Err(Error::TooShort)]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_high_break() {
+        // Each frame starts with a long high preamble and a shorter low pulse.
+        let pulses = [
+            4000, 1500, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 4000, 1500, 1000, 333, 1000,
+            333, 333, 1000, 1000, 333,
+        ];
+        let code = Code {
+            value: 0b1101,
+            length: 4,
+        };
+        let options = DecodeOptions {
+            start: Start::HighBreak,
+            ..Default::default()
+        };
+        assert_eq!(decode_with(&pulses, &options), Ok(code));
+        assert_eq!(decode_all_with(&pulses, &options), vec![Ok(code), Ok(code)]);
+        assert_eq!(
+            decode_detailed(&pulses, &options).unwrap().pulses,
+            &pulses[..12]
+        );
+        assert_ne!(decode(&pulses), Ok(code));
+    }
+
+    #[test]
+    fn decode_inverted_polarity() {
+        let code = Code {
+            value: 0b1101,
+            length: 4,
+        };
+        // Frames start after their break whatever its level.
+        let pulses = [
+            500, 333, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 10000,
+        ];
+        assert_eq!(decode(&pulses), Ok(code));
+        assert_eq!(decode(&pulses[1..]), Ok(code));
+
+        // A capture starting with a low pulse, so high pulses are at odd indices.
+        let pulses = [
+            500, 4000, 1500, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 4000, 1500,
+        ];
+        let options = DecodeOptions {
+            start: Start::HighBreak,
+            ..Default::default()
+        };
+        assert_eq!(decode_with(&pulses, &options), Err(Error::NoStart));
+        let options = DecodeOptions {
+            polarity: Polarity::Inverted,
+            ..options
+        };
+        assert_eq!(decode_with(&pulses, &options), Ok(code));
+
+        let options = DecodeOptions {
+            assume_start: true,
+            polarity: Polarity::Inverted,
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_with(
+                &[500, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 10000],
+                &options
+            ),
+            Ok(code)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn detect_short_duration_drift() {
+        let options = DecodeOptions::default();
+        assert_eq!(
+            decode_detailed(DOORBELL, &options).unwrap().short_duration,
+            317
+        );
+        assert_eq!(detect_drift(DOORBELL, &options, 15), vec![]);
+
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        let mut pulses = code.repeat_pulses(300, 2, 9300);
+        // The last break is shared with the next frame.
+        pulses.pop();
+        pulses.extend(code.repeat_pulses(360, 1, 9300).into_iter().skip(1));
+        assert_eq!(
+            short_durations(&pulses, &options),
+            vec![Ok(300), Ok(300), Ok(360)]
+        );
+        assert_eq!(
+            detect_drift(&pulses, &options, 15),
+            vec![Drift {
+                frame: 2,
+                previous: 300,
+                current: 360
+            }]
+        );
+        assert_eq!(detect_drift(&pulses, &options, 20), vec![]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_frame_by_frame() {
+        let mut pulses = DOORBELL;
+        let mut codes = Vec::new();
+        while let Ok((code, next)) = decode_frame(pulses) {
+            codes.push(Ok(code));
+            pulses = &pulses[next..];
+        }
+        assert_eq!(codes, decode_all(DOORBELL));
+        assert_eq!(decode_frame(&DOORBELL[..52]).unwrap().1, 50);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn observe_pulses() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let options = DecodeOptions {
+            on_pulse: Some(PulseObserver::new({
+                let events = events.clone();
+                move |event| events.lock().unwrap().push(event)
+            })),
+            ..Default::default()
+        };
+        let event = |index, duration, kind| PulseEvent {
+            index,
+            duration,
+            kind,
+        };
+
+        assert_eq!(
+            decode_with(
+                &[100, 10000, 960, 320, 320, 960, 960, 320, 320, 960, 320, 10000],
+                &options
+            ),
+            Ok(Code {
+                value: 0b1010,
+                length: 4
+            })
+        );
+        assert_eq!(
+            events.lock().unwrap().drain(..).collect::<Vec<_>>(),
+            vec![
+                event(0, 100, PulseKind::IgnoredBeforeStart),
+                event(1, 10000, PulseKind::Break),
+                event(2, 960, PulseKind::Long),
+                event(3, 320, PulseKind::Short),
+                event(4, 320, PulseKind::Short),
+                event(5, 960, PulseKind::Long),
+                event(6, 960, PulseKind::Long),
+                event(7, 320, PulseKind::Short),
+                event(8, 320, PulseKind::Short),
+                event(9, 960, PulseKind::Long),
+            ]
+        );
+
+        assert_eq!(
+            decode_with(&[10000, 960, 320, 640, 640, 320, 960, 960, 320], &options),
+            Err(Error::InvalidPulseLength(640, 640))
+        );
+        assert_eq!(
+            events.lock().unwrap().drain(..).collect::<Vec<_>>(),
+            vec![
+                event(0, 10000, PulseKind::Break),
+                event(1, 960, PulseKind::Long),
+                event(2, 320, PulseKind::Short),
+                event(3, 640, PulseKind::Rejected),
+                event(4, 640, PulseKind::Rejected),
+            ]
+        );
+
+        // Decoding every frame reports the break at the end of each frame while searching for the
+        // next one.
+        decode_all_with(DOORBELL, &options);
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), DOORBELL.len());
+        assert!(events
+            .iter()
+            .enumerate()
+            .all(|(index, event)| event.index == index && event.duration == DOORBELL[index]));
+        assert_eq!(options.clone(), options);
+        assert_ne!(options, DecodeOptions::default());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn decode_pair_offset() {
+        // Insert an extra pulse after each break, so symbols start with the low pulse.
+        let pulses = DOORBELL
+            .iter()
+            .flat_map(|&pulse| {
+                if pulse > DEFAULT_BREAK_PULSE_LENGTH {
+                    vec![pulse, 320]
+                } else {
+                    vec![pulse]
+                }
+            })
+            .collect::<Vec<_>>();
+        let options = DecodeOptions {
+            pair_offset: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            decode_with(&pulses, &options),
+            Ok(Code {
+                value: 0x48b2a4,
+                length: 24
+            })
+        );
+        assert_eq!(decode_all_with(&pulses, &options), decode_all(DOORBELL));
+        assert_ne!(
+            decode(&pulses),
+            Ok(Code {
+                value: 0x48b2a4,
+                length: 24
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_code() {
+        use serde_test::{assert_tokens, Configure, Token};
+
+        assert_tokens(
+            &Code {
+                value: 0,
+                length: 12,
+            }
+            .readable(),
+            &[Token::Str("000")],
+        );
+        assert_tokens(
+            &Code {
+                value: 0xf,
+                length: 4,
+            }
+            .readable(),
+            &[Token::Str("f")],
+        );
+        assert_tokens(
+            &Code {
+                value: 0x123456,
+                length: 24,
+            }
+            .readable(),
+            &[Token::Str("123456")],
+        );
+        assert_tokens(
+            &Code {
+                value: 0xabcdef,
+                length: 24,
+            }
+            .readable(),
+            &[Token::Str("abcdef")],
+        );
+        assert_tokens(
+            &Code {
+                value: 0xff112233,
+                length: 32,
+            }
+            .readable(),
+            &[Token::Str("ff112233")],
+        );
+        assert_tokens(
+            &Code {
+                value: 0x0123456789abcd,
+                length: 56,
+            }
+            .readable(),
+            &[Token::Str("0123456789abcd")],
+        );
+        assert_tokens(
+            &Code {
+                value: 0x4763a,
+                length: 19,
+            }
+            .readable(),
+            &[Token::Str("19bit:0x4763a")],
+        );
+        assert_tokens(
+            &Code {
+                value: 0x3,
+                length: 10,
+            }
+            .readable(),
+            &[Token::Str("10bit:0x3")],
+        );
+        assert_tokens(
+            &Code {
+                value: 0,
+                length: 0,
+            }
+            .readable(),
+            &[Token::Str("0bit:0x0")],
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_code_compact() {
+        use serde_test::{
+            assert_de_tokens, assert_de_tokens_error, assert_tokens, Configure, Token,
+        };
+
+        assert_tokens(
+            &Code {
+                value: 0x4763a,
+                length: 19,
+            }
+            .compact(),
+            &[
+                Token::Tuple { len: 2 },
+                Token::Bytes(&[0x04, 0x76, 0x3a]),
+                Token::U8(19),
+                Token::TupleEnd,
+            ],
+        );
+        assert_tokens(
+            &Code {
+                value: 0x12_3456_789a,
+                length: 40,
+            }
+            .compact(),
+            &[
+                Token::Tuple { len: 2 },
+                Token::Bytes(&[0x12, 0x34, 0x56, 0x78, 0x9a]),
+                Token::U8(40),
+                Token::TupleEnd,
+            ],
+        );
+        assert_tokens(
+            &Code {
+                value: 0,
+                length: 0,
+            }
+            .compact(),
+            &[
+                Token::Tuple { len: 2 },
+                Token::Bytes(&[]),
+                Token::U8(0),
+                Token::TupleEnd,
+            ],
+        );
+        assert_de_tokens(
+            &Code {
+                value: 0x4763a,
+                length: 19,
+            }
+            .compact(),
+            &[
+                Token::Tuple { len: 2 },
+                Token::Seq { len: Some(3) },
+                Token::U8(0x04),
+                Token::U8(0x76),
+                Token::U8(0x3a),
+                Token::SeqEnd,
+                Token::U8(19),
+                Token::TupleEnd,
+            ],
+        );
+        assert_de_tokens_error::<serde_test::Compact<Code>>(
+            &[
+                Token::Tuple { len: 2 },
+                Token::Bytes(&[0x04, 0x76, 0x3a]),
+                Token::U8(8),
+                Token::TupleEnd,
+            ],
+            "Value 0x4763a doesn't fit in 8 bits",
+        );
+        assert_de_tokens_error::<serde_test::Compact<Code>>(
+            &[
+                Token::Tuple { len: 2 },
+                Token::Bytes(&[1; 17]),
+                Token::U8(136),
+                Token::TupleEnd,
+            ],
+            "invalid length 17, expected no more than 16 bytes",
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn messagepack_round_trip() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        let bytes = rmp_serde::to_vec(&code).unwrap();
+        // A fixarray of 2, a bin 8 of 3 bytes, then a positive fixint.
+        assert_eq!(bytes, [0x92, 0xc4, 3, 0x48, 0xb2, 0xa4, 24]);
+        assert_eq!(rmp_serde::from_slice::<Code>(&bytes).unwrap(), code);
     }
 }
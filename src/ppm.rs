@@ -0,0 +1,101 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{apply_bit_order, next_start, verify_checksum, Code, DecodeOptions, Error};
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a button code which uses pulse-position modulation with slots of `slot` microseconds.
+///
+/// After the start of the frame, the first high pulse marks the start of the first slot and
+/// doesn't carry any data. Each following slot must contain exactly one high pulse, which encodes
+/// a 0 if it starts in the first half of the slot or a 1 if it starts in the second half. The frame
+/// ends at the next break.
+///
+/// The start detection, break pulse length, minimum length, bit order and checksum from `options`
+/// are used; the symbol multiples are not.
+///
+/// Returns [`Error::NoSignal`] if `slot` is 0.
+pub fn decode_ppm(pulses: &[u16], slot: u16, options: &DecodeOptions) -> Result<Code, Error> {
+    if pulses.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+    let start = next_start(pulses, 0, options, 1).ok_or(Error::NoStart)?;
+    let frame = &pulses[start..];
+    if frame.len() < 2 {
+        return Err(Error::TooShort);
+    }
+    if slot == 0 {
+        return Err(Error::NoSignal);
+    }
+    let slot = u32::from(slot);
+
+    let mut value = 0;
+    let mut length: u8 = 0;
+    // The start time of the current high pulse, relative to the start of the marker pulse.
+    let mut time = u32::from(frame[0]) + u32::from(frame[1]);
+    if frame[1] <= options.break_pulse_length {
+        for pair in frame[2..].chunks_exact(2) {
+            let (high, low) = (pair[0], pair[1]);
+            if time / slot != u32::from(length) + 1 {
+                return Err(Error::InvalidPulseLength(high, low));
+            }
+            if u32::from(length) >= u128::BITS {
+                return Err(Error::TooLong);
+            }
+            let bit = time % slot >= slot / 2;
+            value = value << 1 | u128::from(bit);
+            length += 1;
+            if low > options.break_pulse_length {
+                break;
+            }
+            time += u32::from(high) + u32::from(low);
+        }
+    }
+
+    if length < options.min_bits {
+        return Err(Error::TooShort);
+    }
+    verify_checksum(apply_bit_order(Code { value, length }, options), options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_slots() {
+        // Slots of 1000 μs, with pulses starting 100 μs into a slot for 0 or 600 μs for 1.
+        let pulses = [
+            200, 9000, 200, 1400, 200, 300, 200, 1300, 200, 800, 200, 9000,
+        ];
+        assert_eq!(
+            decode_ppm(&pulses, 1000, &DecodeOptions::default()),
+            Ok(Code {
+                value: 0b1011,
+                length: 4
+            })
+        );
+    }
+
+    #[test]
+    fn missing_slot() {
+        // The pulse for the second slot is missing, so the next one is in the third slot.
+        let pulses = [200, 9000, 200, 1400, 200, 1300, 200, 800, 200, 9000];
+        assert_eq!(
+            decode_ppm(&pulses, 1000, &DecodeOptions::default()),
+            Err(Error::InvalidPulseLength(200, 800))
+        );
+    }
+
+    #[test]
+    fn zero_slot() {
+        let pulses = [
+            200, 9000, 200, 1400, 200, 300, 200, 1300, 200, 800, 200, 9000,
+        ];
+        assert_eq!(
+            decode_ppm(&pulses, 0, &DecodeOptions::default()),
+            Err(Error::NoSignal)
+        );
+    }
+}
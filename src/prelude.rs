@@ -0,0 +1,18 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Re-exports of the most commonly used types and functions, to import them all at once with
+//! `use rfbutton::prelude::*`.
+
+pub use crate::{
+    decode, decode_with, AmbiguityPolicy, BitOrder, Checksum, Code, DecodeOptions, Error, FrameEnd,
+    PulseDecoder, Start,
+};
+#[cfg(feature = "alloc")]
+pub use crate::{
+    decode_all, decode_all_with, encode, recognize, EdgeCapture, FrameSplitter, Protocol,
+    ProtocolRegistry,
+};
+#[cfg(feature = "std")]
+pub use crate::{CodeBook, Debouncer};
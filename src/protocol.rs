@@ -0,0 +1,473 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! A common interface for the different encodings used by remotes, so that they can be tried in
+//! turn on a capture from an unknown remote.
+
+use crate::{
+    apply_bit_order, decode_biphase, decode_keeloq, decode_ppm, decode_with,
+    keeloq::{FRAME_BITS, HEADER_MULTIPLE},
+    rcswitch::decode_with_protocol,
+    Code, DecodeOptions, Error, RcSwitchProtocol, DEFAULT_SHORT_DURATION, SYNC_MULTIPLE,
+};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::fmt::{self, Debug, Formatter};
+
+/// A way of encoding button codes as pulses, which can be implemented outside this crate and added
+/// to a [`ProtocolRegistry`].
+pub trait Protocol {
+    /// Returns a short name for the protocol, such as `"pwm"`.
+    fn name(&self) -> &str;
+
+    /// Given a sequence of pulse durations in microseconds (starting with a high pulse), tries to
+    /// decode a button code from the first frame.
+    fn decode(&self, pulses: &[u16]) -> Result<Code, Error>;
+
+    /// Encodes a button code as a single frame of pulse durations in microseconds (starting with a
+    /// high pulse), with a break before and after it, which [`decode`](Self::decode) will decode
+    /// back to the same code.
+    fn encode(&self, code: &Code) -> Vec<u16>;
+}
+
+/// Pulse-width modulation, where each bit is a high pulse followed by a low pulse and their
+/// lengths distinguish a 1 from a 0, as decoded by [`decode_with`].
+///
+/// The default is the same as [`decode`](crate::decode) and [`encode`](crate::encode) use, as sent
+/// by PT2262 and EV1527 encoders.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PwmProtocol {
+    /// The name of the protocol.
+    pub name: &'static str,
+    /// The options to decode with, including the pulse length multiples of each bit.
+    pub options: DecodeOptions,
+    /// The short pulse duration in microseconds to encode with.
+    pub short_duration: u16,
+}
+
+impl Default for PwmProtocol {
+    fn default() -> Self {
+        Self {
+            name: "pwm",
+            options: DecodeOptions::default(),
+            short_duration: DEFAULT_SHORT_DURATION,
+        }
+    }
+}
+
+impl Protocol for PwmProtocol {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn decode(&self, pulses: &[u16]) -> Result<Code, Error> {
+        decode_with(pulses, &self.options)
+    }
+
+    /// Encodes the code as for [`Code::to_pulses`], but with the pulse length multiples from
+    /// `options`, in the order given by `options.bit_order`. Other options such as a sync word are
+    /// not added.
+    fn encode(&self, code: &Code) -> Vec<u16> {
+        let short_duration = self.short_duration;
+        apply_bit_order(*code, &self.options).pwm_pulses(
+            short_duration,
+            self.options.one,
+            self.options.zero,
+            1,
+            short_duration.saturating_mul(SYNC_MULTIPLE),
+        )
+    }
+}
+
+/// Bi-phase mark encoding, as decoded by [`decode_biphase`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BiphaseProtocol {
+    /// The name of the protocol.
+    pub name: &'static str,
+    /// The options to decode with.
+    pub options: DecodeOptions,
+    /// The half bit duration in microseconds to encode with.
+    pub half_bit: u16,
+}
+
+impl Default for BiphaseProtocol {
+    fn default() -> Self {
+        Self {
+            name: "biphase",
+            options: DecodeOptions::default(),
+            half_bit: 400,
+        }
+    }
+}
+
+impl Protocol for BiphaseProtocol {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn decode(&self, pulses: &[u16]) -> Result<Code, Error> {
+        decode_biphase(pulses, &self.options)
+    }
+
+    /// Encodes each 1 as two half bit pulses and each 0 as a single full bit pulse, with a break of
+    /// 31 times the half bit duration before and after the frame. The bits are sent in the order
+    /// given by `options.bit_order`.
+    fn encode(&self, code: &Code) -> Vec<u16> {
+        let code = apply_bit_order(*code, &self.options);
+        let half_bit = self.half_bit;
+        let sync = half_bit.saturating_mul(SYNC_MULTIPLE);
+        let mut pulses = vec![half_bit, sync];
+        for bit in code.bits() {
+            if bit {
+                pulses.extend([half_bit, half_bit]);
+            } else {
+                pulses.push(half_bit.saturating_mul(2));
+            }
+        }
+        pulses.push(sync);
+        pulses
+    }
+}
+
+/// The protocols of the rc-switch library, as decoded by
+/// [`decode_rcswitch`](crate::decode_rcswitch).
+impl Protocol for RcSwitchProtocol {
+    fn name(&self) -> &str {
+        "rcswitch"
+    }
+
+    fn decode(&self, pulses: &[u16]) -> Result<Code, Error> {
+        decode_with_protocol(pulses, self)
+    }
+
+    /// Encodes the code as rc-switch sends it, with a sync pulse before and after the bits. If the
+    /// protocol is inverted then a short high pulse is added first, so the pulses start with a high
+    /// pulse.
+    ///
+    /// rc-switch can't receive codes longer than 32 bits.
+    fn encode(&self, code: &Code) -> Vec<u16> {
+        let pulse = |multiple: u8| self.pulse_length.saturating_mul(multiple.into());
+        let sync = [pulse(self.sync.0), pulse(self.sync.1)];
+        let mut pulses = if self.inverted {
+            vec![self.pulse_length]
+        } else {
+            Vec::new()
+        };
+        pulses.extend(sync);
+        for bit in code.bits() {
+            let (first, second) = if bit { self.one } else { self.zero };
+            pulses.extend([pulse(first), pulse(second)]);
+        }
+        pulses.extend(sync);
+        pulses
+    }
+}
+
+/// Pulse-position modulation, as decoded by [`decode_ppm`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PpmProtocol {
+    /// The name of the protocol.
+    pub name: &'static str,
+    /// The length of each slot in microseconds.
+    pub slot: u16,
+    /// The options to decode with.
+    pub options: DecodeOptions,
+}
+
+impl Default for PpmProtocol {
+    fn default() -> Self {
+        Self {
+            name: "ppm",
+            slot: 1000,
+            options: DecodeOptions::default(),
+        }
+    }
+}
+
+impl Protocol for PpmProtocol {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn decode(&self, pulses: &[u16]) -> Result<Code, Error> {
+        decode_ppm(pulses, self.slot, &self.options)
+    }
+
+    /// Encodes each bit as a high pulse of a fifth of a slot, starting a tenth of the way into its
+    /// slot for a 0 or six tenths for a 1, after a marker pulse at the start of the first slot. The
+    /// breaks before and after the frame are 3 times `options.break_pulse_length`.
+    ///
+    /// Slots longer than about 2000 μs may have low pulses longer than the break pulse length, so
+    /// won't decode.
+    fn encode(&self, code: &Code) -> Vec<u16> {
+        let code = apply_bit_order(*code, &self.options);
+        let slot = u32::from(self.slot);
+        let high = slot / 5;
+        let offset = |bit: bool| if bit { slot * 6 / 10 } else { slot / 10 };
+        let micros = |duration: u32| duration.try_into().unwrap_or(u16::MAX);
+        let gap = self.options.break_pulse_length.saturating_mul(3);
+        let mut pulses = vec![micros(high), gap, micros(high)];
+        // The offset into its slot of the last high pulse, starting with the marker.
+        let mut last_offset = 0;
+        for bit in code.bits() {
+            pulses.push(micros(slot + offset(bit) - last_offset - high));
+            pulses.push(micros(high));
+            last_offset = offset(bit);
+        }
+        pulses.push(gap);
+        pulses
+    }
+}
+
+/// KeeLoq rolling code frames, as decoded by [`decode_keeloq`].
+///
+/// The code is the 66 bits of the frame, with the first bit sent as the least significant bit, as
+/// converted from a [`KeeloqFrame`](crate::KeeloqFrame).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeloqProtocol {
+    /// The name of the protocol.
+    pub name: &'static str,
+    /// The duration of the basic pulse element in microseconds to encode with.
+    pub element: u16,
+}
+
+impl Default for KeeloqProtocol {
+    fn default() -> Self {
+        Self {
+            name: "keeloq",
+            element: 400,
+        }
+    }
+}
+
+impl Protocol for KeeloqProtocol {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn decode(&self, pulses: &[u16]) -> Result<Code, Error> {
+        decode_keeloq(pulses).map(Code::from)
+    }
+
+    /// Encodes the low 66 bits of the code after a preamble and header, with a guard time of 39
+    /// elements before and after the frame.
+    fn encode(&self, code: &Code) -> Vec<u16> {
+        let element = self.element;
+        let guard = element.saturating_mul(39);
+        let mut pulses = vec![element, guard];
+        for _ in 0..11 {
+            pulses.extend([element, element]);
+        }
+        pulses.extend([element, element.saturating_mul(HEADER_MULTIPLE as u16)]);
+        for i in 0..FRAME_BITS {
+            if code.value >> i & 1 == 1 {
+                pulses.extend([element, element.saturating_mul(2)]);
+            } else {
+                pulses.extend([element.saturating_mul(2), element]);
+            }
+        }
+        // The low pulse of the last bit runs into the guard time.
+        if let Some(last) = pulses.last_mut() {
+            *last = guard;
+        }
+        pulses
+    }
+}
+
+/// A list of protocols to try in turn when decoding a capture from an unknown remote.
+///
+/// The default registry contains the default [`PwmProtocol`] followed by the default
+/// [`BiphaseProtocol`].
+pub struct ProtocolRegistry {
+    protocols: Vec<Box<dyn Protocol + Send + Sync>>,
+}
+
+impl ProtocolRegistry {
+    /// Creates a new registry with no protocols, unlike the default registry.
+    pub fn empty() -> Self {
+        Self {
+            protocols: Vec::new(),
+        }
+    }
+
+    /// Adds a protocol to the end of the registry, so it is tried after those already registered.
+    pub fn register(&mut self, protocol: impl Protocol + Send + Sync + 'static) {
+        self.protocols.push(Box::new(protocol));
+    }
+
+    /// Returns the first registered protocol with the given name, if any.
+    pub fn get(&self, name: &str) -> Option<&(dyn Protocol + Send + Sync)> {
+        self.protocols
+            .iter()
+            .map(Box::as_ref)
+            .find(|protocol| protocol.name() == name)
+    }
+
+    /// Given a sequence of pulse durations in microseconds (starting with a high pulse), tries to
+    /// decode it with each registered protocol in order, and returns the code from the first which
+    /// succeeds along with the protocol.
+    ///
+    /// Protocols are often similar enough that more than one can decode the same pulses, so they
+    /// should be registered from the most to the least specific. If none succeed then the error
+    /// from the first protocol is returned, or [`Error::NoStart`] if there are none.
+    pub fn decode_any(
+        &self,
+        pulses: &[u16],
+    ) -> Result<(Code, &(dyn Protocol + Send + Sync)), Error> {
+        let mut first_error = None;
+        for protocol in &self.protocols {
+            match protocol.decode(pulses) {
+                Ok(code) => return Ok((code, protocol.as_ref())),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        Err(first_error.unwrap_or(Error::NoStart))
+    }
+}
+
+impl Default for ProtocolRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(PwmProtocol::default());
+        registry.register(BiphaseProtocol::default());
+        registry
+    }
+}
+
+impl Debug for ProtocolRegistry {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.protocols.iter().map(|protocol| protocol.name()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BitOrder, RCSWITCH_PROTOCOLS};
+
+    /// A protocol which sends the value as the number of pulses.
+    struct Count;
+
+    impl Protocol for Count {
+        fn name(&self) -> &str {
+            "count"
+        }
+
+        fn decode(&self, pulses: &[u16]) -> Result<Code, Error> {
+            Code::new(pulses.len() as u128, 8)
+        }
+
+        fn encode(&self, code: &Code) -> Vec<u16> {
+            vec![100; code.value as usize]
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        let registry = ProtocolRegistry::default();
+        for name in ["pwm", "biphase"] {
+            let protocol = registry.get(name).unwrap();
+            let (decoded, matched) = registry.decode_any(&protocol.encode(&code)).unwrap();
+            assert_eq!(decoded, code);
+            assert_eq!(matched.name(), name);
+        }
+    }
+
+    #[test]
+    fn round_trip_other_protocols() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        let mut registry = ProtocolRegistry::empty();
+        registry.register(PwmProtocol {
+            name: "pwm-2-1",
+            options: DecodeOptions {
+                one: (2, 1),
+                zero: (1, 2),
+                ..Default::default()
+            },
+            short_duration: 500,
+        });
+        registry.register(RCSWITCH_PROTOCOLS[5]);
+        registry.register(PpmProtocol::default());
+        for protocol in [
+            registry.get("pwm-2-1").unwrap(),
+            registry.get("rcswitch").unwrap(),
+            registry.get("ppm").unwrap(),
+        ] {
+            assert_eq!(protocol.decode(&protocol.encode(&code)), Ok(code));
+        }
+
+        let keeloq = KeeloqProtocol::default();
+        let code = Code {
+            value: 0x1234_5678 | 0x0abc_def1 << 32 | 0x5 << 60 | 1 << 65,
+            length: 66,
+        };
+        assert_eq!(keeloq.decode(&keeloq.encode(&code)), Ok(code));
+    }
+
+    #[test]
+    fn round_trip_lsb_first() {
+        let code = Code {
+            value: 0x48b2a4,
+            length: 24,
+        };
+        let options = DecodeOptions {
+            bit_order: BitOrder::LsbFirst,
+            ..Default::default()
+        };
+        let protocols: [Box<dyn Protocol>; 3] = [
+            Box::new(PwmProtocol {
+                options: options.clone(),
+                ..Default::default()
+            }),
+            Box::new(BiphaseProtocol {
+                options: options.clone(),
+                ..Default::default()
+            }),
+            Box::new(PpmProtocol {
+                options,
+                ..Default::default()
+            }),
+        ];
+        for protocol in protocols {
+            assert_eq!(
+                protocol.decode(&protocol.encode(&code)),
+                Ok(code),
+                "{}",
+                protocol.name()
+            );
+        }
+    }
+
+    #[test]
+    fn custom_protocol() {
+        let mut registry = ProtocolRegistry::default();
+        registry.register(Count);
+        assert_eq!(format!("{:?}", registry), r#"["pwm", "biphase", "count"]"#);
+        let (code, protocol) = registry.decode_any(&[300, 200, 100]).unwrap();
+        assert_eq!(protocol.name(), "count");
+        assert_eq!(code.value, 3);
+    }
+
+    #[test]
+    fn no_match() {
+        assert_eq!(
+            ProtocolRegistry::empty().decode_any(&[300, 10000]).err(),
+            Some(Error::NoStart)
+        );
+        assert_eq!(
+            ProtocolRegistry::default().decode_any(&[]).err(),
+            Some(Error::EmptyInput)
+        );
+    }
+}
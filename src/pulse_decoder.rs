@@ -0,0 +1,315 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::{decode_scaled, Code, DecodeOptions, Error, FrameEnd};
+
+/// Decodes button codes from a stream of pulses as they arrive, such as from an interrupt handler.
+///
+/// Pulses are buffered in a fixed-size array of `N` pulses rather than allocated, so frames which
+/// don't fit are skipped. The buffer holds a frame along with the break before and after it, and
+/// the pulse before the first break, so the default of 260 is enough for frames of up to 128 bits.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PulseDecoder<const N: usize = 260> {
+    options: DecodeOptions,
+    pulses: [u16; N],
+    /// The number of pulses in `pulses`, which start at an even position in the stream.
+    len: usize,
+    /// Whether the current frame didn't fit in `pulses`, so the rest of it is being skipped.
+    skipping: bool,
+    /// Whether an odd number of pulses have been fed so far.
+    odd: bool,
+}
+
+impl<const N: usize> PulseDecoder<N> {
+    /// Creates a new decoder which decodes frames with the given options.
+    ///
+    /// Each time a pulse longer than `options.break_pulse_length` arrives, the pulses so far are
+    /// decoded as if they were passed to [`decode_with`](crate::decode_with), so frames are found
+    /// in the same way with any `options.start`, `options.assume_start` or
+    /// `options.break_multiple`. Frames must still end with such a pulse to be decoded.
+    ///
+    /// If `options.break_multiple` is set then a frame which is too short when such a pulse arrives
+    /// is assumed to continue past it.
+    pub fn new(options: DecodeOptions) -> Self {
+        // There must be room for a break and the pulse before it.
+        const { assert!(N >= 2, "PulseDecoder needs room for at least 2 pulses") };
+        Self {
+            options,
+            pulses: [0; N],
+            len: 0,
+            skipping: false,
+            odd: false,
+        }
+    }
+
+    /// Adds the next pulse duration in microseconds, starting with a high pulse, returning the
+    /// code of the frame which it ended if there was one.
+    ///
+    /// Frames which fail to decode are ignored, as are any pulses before the first frame.
+    pub fn feed(&mut self, pulse: u16) -> Option<Code> {
+        let odd = self.odd;
+        self.odd = !odd;
+        if self.len == N {
+            // The frame is too long, so skip it.
+            self.skipping = true;
+        }
+        if self.skipping {
+            if pulse <= self.options.break_pulse_length {
+                return None;
+            }
+            // Start again from the break, after a placeholder to keep it at the same position
+            // relative to the high pulses.
+            self.skipping = false;
+            self.len = 0;
+            if odd {
+                self.pulses[0] = 0;
+                self.len = 1;
+            }
+        }
+        self.pulses[self.len] = pulse;
+        self.len += 1;
+        if pulse <= self.options.break_pulse_length {
+            return None;
+        }
+
+        let code = match decode_scaled(&self.pulses[..self.len], &self.options, 1, &mut ()) {
+            // If `options.break_multiple` is set then this pulse may be part of a frame which
+            // continues after it.
+            Ok((_, frame))
+                if self.options.break_multiple.is_some()
+                    && matches!(frame.end, FrameEnd::Complete | FrameEnd::Truncated) =>
+            {
+                return None;
+            }
+            Err(Error::TooShort) if self.options.break_multiple.is_some() => return None,
+            Ok((_, frame)) => Some(frame.code),
+            Err(_) => None,
+        };
+        // The break at the end of one frame may also start the next.
+        self.keep_from(self.len - 1);
+        code
+    }
+
+    /// Decodes the bits of the current frame received so far, without waiting for the break at
+    /// the end, such as to show a code growing as it arrives.
+    ///
+    /// Returns `None` if no frame has started yet, the current frame is being skipped because it
+    /// didn't fit, or its pulses so far can't be decoded, such as because there are too few of
+    /// them to estimate the short pulse duration.
+    ///
+    /// This decodes the whole frame again each time it is called.
+    pub fn partial(&self) -> Option<Code> {
+        if self.len == 0 || self.skipping {
+            return None;
+        }
+        decode_scaled(&self.pulses[..self.len], &self.options, 1, &mut ())
+            .ok()
+            .map(|(_, frame)| frame.code)
+    }
+
+    /// Discards any pulses of the current frame, and waits for the next break.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.skipping = true;
+    }
+
+    /// Discards the pulses before `index`, apart from one if necessary to keep the rest at the same
+    /// positions relative to the high pulses.
+    fn keep_from(&mut self, index: usize) {
+        let from = index & !1;
+        self.pulses.copy_within(from..self.len, 0);
+        self.len -= from;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_with, Start};
+
+    #[test]
+    fn decode_frames() {
+        let mut decoder = PulseDecoder::<128>::new(DecodeOptions::default());
+        let codes = [
+            300, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 333, 10000, 1000, 333, 333,
+            1000, 333, 10000,
+        ]
+        .into_iter()
+        .filter_map(|pulse| decoder.feed(pulse))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            vec![
+                Code {
+                    value: 0b1101,
+                    length: 4
+                },
+                Code {
+                    value: 0b10,
+                    length: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn skip_long_frames() {
+        let mut decoder = PulseDecoder::<7>::new(DecodeOptions::default());
+        for pulse in [10000, 1000, 333, 333, 1000, 1000, 333] {
+            assert_eq!(decoder.feed(pulse), None);
+        }
+        // The frame was too long, so the next break only starts a new frame. It is a low pulse, so
+        // it takes 2 spaces in the buffer.
+        assert_eq!(decoder.feed(10000), None);
+        for pulse in [1000, 333, 333, 1000] {
+            assert_eq!(decoder.feed(pulse), None);
+        }
+        assert_eq!(
+            decoder.feed(10000),
+            Some(Code {
+                value: 0b10,
+                length: 2
+            })
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn longest_code() {
+        let code = Code {
+            value: u128::MAX - 1,
+            length: 128,
+        };
+        let mut decoder: PulseDecoder = PulseDecoder::new(DecodeOptions::default());
+        let codes = code
+            .repeat_pulses(300, 2, 9300)
+            .into_iter()
+            .filter_map(|pulse| decoder.feed(pulse))
+            .collect::<Vec<_>>();
+        assert_eq!(codes, vec![code, code]);
+    }
+
+    #[test]
+    fn partial() {
+        let mut decoder = PulseDecoder::<128>::new(DecodeOptions::default());
+        assert_eq!(decoder.partial(), None);
+        let partials = [
+            300, 10000, 1000, 333, 1000, 333, 333, 1000, 1000, 333, 10000,
+        ]
+        .into_iter()
+        .map(|pulse| {
+            decoder.feed(pulse);
+            decoder.partial()
+        })
+        .collect::<Vec<_>>();
+        let code = |value, length| Some(Code { value, length });
+        assert_eq!(
+            partials,
+            vec![
+                None,
+                None,
+                None,
+                None,
+                None,
+                code(0b11, 2),
+                code(0b11, 2),
+                code(0b110, 3),
+                code(0b110, 3),
+                code(0b1101, 4),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn reset() {
+        let mut decoder = PulseDecoder::<128>::new(DecodeOptions::default());
+        for pulse in [10000, 1000, 333, 333, 1000] {
+            assert_eq!(decoder.feed(pulse), None);
+        }
+        decoder.reset();
+        assert_eq!(decoder.partial(), None);
+        assert_eq!(decoder.feed(10000), None);
+    }
+
+    /// Feeds all the given pulses to a decoder with the given options, and returns the codes.
+    fn feed_all(pulses: &[u16], options: DecodeOptions) -> Vec<Code> {
+        let mut decoder = PulseDecoder::<128>::new(options);
+        pulses
+            .iter()
+            .filter_map(|&pulse| decoder.feed(pulse))
+            .collect()
+    }
+
+    #[test]
+    fn high_break() {
+        let pulses = [
+            10000, 300, 1000, 333, 333, 1000, 10000, 300, 1000, 333, 1000, 333, 10000, 300,
+        ];
+        // Starting straight after each break rather than after the low pulse which follows it, only
+        // the second frame happens to decode.
+        assert_eq!(
+            feed_all(&pulses, DecodeOptions::default()),
+            vec![Code {
+                value: 0b00,
+                length: 2
+            }]
+        );
+        assert_eq!(
+            feed_all(
+                &pulses,
+                DecodeOptions {
+                    start: Start::HighBreak,
+                    ..Default::default()
+                }
+            ),
+            vec![
+                Code {
+                    value: 0b10,
+                    length: 2
+                },
+                Code {
+                    value: 0b11,
+                    length: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn assume_start() {
+        let pulses = [1000, 333, 333, 1000, 10000];
+        assert_eq!(feed_all(&pulses, DecodeOptions::default()), vec![]);
+        assert_eq!(
+            feed_all(
+                &pulses,
+                DecodeOptions {
+                    assume_start: true,
+                    ..Default::default()
+                }
+            ),
+            vec![Code {
+                value: 0b10,
+                length: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn break_multiple() {
+        // The long pulses are longer than the break pulse length, but shorter than 10 times the
+        // short pulse duration.
+        let pulses = [1200, 37200, 3600, 1200, 1200, 3600, 3600, 1200, 1200, 37200];
+        let options = DecodeOptions {
+            break_multiple: Some(10),
+            ..Default::default()
+        };
+        let code = Code {
+            value: 0b101,
+            length: 3,
+        };
+        assert_eq!(decode_with(&pulses, &options), Ok(code));
+        assert_eq!(feed_all(&pulses, options), vec![code]);
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Compatibility with the protocols of the Arduino
+//! [rc-switch](https://github.com/sui77/rc-switch) library.
+
+use crate::{Code, Error};
+
+/// The minimum length in microseconds of a pulse for rc-switch to treat it as the gap between two
+/// transmissions.
+const SEPARATION_LIMIT: u16 = 4300;
+
+/// The tolerance of pulse lengths as a percentage of the base pulse length, as rc-switch uses by
+/// default.
+const RECEIVE_TOLERANCE: u32 = 60;
+
+/// A timing protocol as defined by rc-switch.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RcSwitchProtocol {
+    /// The base pulse length in microseconds, used when transmitting.
+    pub pulse_length: u16,
+    /// The high and low lengths of the sync pulse, as multiples of the base pulse length.
+    pub sync: (u8, u8),
+    /// The high and low lengths of a 0 bit, as multiples of the base pulse length.
+    pub zero: (u8, u8),
+    /// The high and low lengths of a 1 bit, as multiples of the base pulse length.
+    pub one: (u8, u8),
+    /// Whether the signal is inverted, so each pair of pulses starts with a low pulse.
+    pub inverted: bool,
+}
+
+/// The protocols built in to rc-switch, in order. Note that rc-switch numbers these from 1.
+pub const RCSWITCH_PROTOCOLS: [RcSwitchProtocol; 7] = [
+    RcSwitchProtocol {
+        pulse_length: 350,
+        sync: (1, 31),
+        zero: (1, 3),
+        one: (3, 1),
+        inverted: false,
+    },
+    RcSwitchProtocol {
+        pulse_length: 650,
+        sync: (1, 10),
+        zero: (1, 2),
+        one: (2, 1),
+        inverted: false,
+    },
+    RcSwitchProtocol {
+        pulse_length: 100,
+        sync: (30, 71),
+        zero: (4, 11),
+        one: (9, 6),
+        inverted: false,
+    },
+    RcSwitchProtocol {
+        pulse_length: 380,
+        sync: (1, 6),
+        zero: (1, 3),
+        one: (3, 1),
+        inverted: false,
+    },
+    RcSwitchProtocol {
+        pulse_length: 500,
+        sync: (6, 14),
+        zero: (1, 2),
+        one: (2, 1),
+        inverted: false,
+    },
+    // HT6P20B
+    RcSwitchProtocol {
+        pulse_length: 450,
+        sync: (23, 1),
+        zero: (1, 2),
+        one: (2, 1),
+        inverted: true,
+    },
+    // HS2303-PT
+    RcSwitchProtocol {
+        pulse_length: 150,
+        sync: (2, 62),
+        zero: (1, 6),
+        one: (6, 1),
+        inverted: false,
+    },
+];
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a button code in the same way as rc-switch does for the given protocol number (starting from 1).
+///
+/// The value and length of the resulting code match those which rc-switch reports.
+pub fn decode_rcswitch(pulses: &[u16], protocol: usize) -> Result<Code, Error> {
+    let protocol = protocol
+        .checked_sub(1)
+        .and_then(|index| RCSWITCH_PROTOCOLS.get(index))
+        .ok_or(Error::UnknownProtocol(protocol))?;
+    decode_with_protocol(pulses, protocol)
+}
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a button code in the same way as rc-switch does for the given protocol.
+pub(crate) fn decode_with_protocol(
+    pulses: &[u16],
+    protocol: &RcSwitchProtocol,
+) -> Result<Code, Error> {
+    if pulses.is_empty() {
+        return Err(Error::EmptyInput);
+    }
+
+    // Find the gap before the transmission, and take all the pulses up to the next gap.
+    let start = pulses
+        .iter()
+        .position(|&pulse| pulse > SEPARATION_LIMIT)
+        .ok_or(Error::NoStart)?;
+    let timings = &pulses[start..];
+    let end = timings[1..]
+        .iter()
+        .position(|&pulse| pulse > SEPARATION_LIMIT)
+        .map_or(timings.len(), |end| end + 1);
+    let timings = &timings[..end];
+    // rc-switch ignores very short transmissions as noise.
+    if timings.len() <= 7 {
+        return Err(Error::TooShort);
+    }
+
+    // The gap is the long part of the sync pulse, which gives the base pulse length.
+    let sync_length = protocol.sync.0.max(protocol.sync.1);
+    let delay = u32::from(timings[0]) / u32::from(sync_length);
+    let tolerance = delay * RECEIVE_TOLERANCE / 100;
+    let matches = |timing: u16, multiple: u8| {
+        u32::from(timing).abs_diff(delay * u32::from(multiple)) < tolerance
+    };
+
+    // rc-switch keeps the value in an `unsigned long`, so can't receive longer codes.
+    if (timings.len() - 1) / 2 > u32::BITS as usize {
+        return Err(Error::TooLong);
+    }
+
+    let first_data_timing = if protocol.inverted { 2 } else { 1 };
+    let mut value: u32 = 0;
+    for i in (first_data_timing..timings.len() - 1).step_by(2) {
+        let (first, second) = (timings[i], timings[i + 1]);
+        let bit = if matches(first, protocol.zero.0) && matches(second, protocol.zero.1) {
+            0
+        } else if matches(first, protocol.one.0) && matches(second, protocol.one.1) {
+            1
+        } else {
+            return Err(Error::InvalidPulseLength(first, second));
+        };
+        value = value << 1 | bit;
+    }
+
+    Ok(Code {
+        value: value.into(),
+        length: ((timings.len() - 1) / 2) as u8,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn protocol_1() {
+        let code = Code {
+            value: 5393,
+            length: 24,
+        };
+        assert_eq!(decode_rcswitch(&code.to_pulses(350), 1), Ok(code));
+    }
+
+    #[test]
+    fn protocol_2() {
+        assert_eq!(
+            decode_rcswitch(
+                &[650, 6500, 1300, 650, 650, 1300, 1300, 650, 1300, 650, 650, 6500],
+                2
+            ),
+            Ok(Code {
+                value: 0b1011,
+                length: 4
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_protocol() {
+        assert_eq!(decode_rcswitch(&[], 0), Err(Error::UnknownProtocol(0)));
+        assert_eq!(decode_rcswitch(&[], 8), Err(Error::UnknownProtocol(8)));
+        assert_eq!(decode_rcswitch(&[], 1), Err(Error::EmptyInput));
+    }
+
+    #[test]
+    fn too_short() {
+        assert_eq!(
+            decode_rcswitch(&[350, 10850, 1050, 350, 350, 10850], 1),
+            Err(Error::TooShort)
+        );
+    }
+}
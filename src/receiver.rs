@@ -0,0 +1,561 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Capturing pulse sequences from a receiver connected to a GPIO pin.
+
+use crate::DEFAULT_BREAK_PULSE_LENGTH;
+use alloc::vec::Vec;
+use core::{mem::take, time::Duration};
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// The default maximum time to wait for an edge once a code has started, after which the capture
+/// is considered complete.
+pub const DEFAULT_MAX_PULSE_LENGTH: Duration = Duration::from_millis(10);
+
+/// A monotonic source of timestamps, used to measure pulse lengths.
+pub trait Clock {
+    /// Returns the time elapsed since some fixed point in the past.
+    fn now(&self) -> Duration;
+}
+
+/// An instant can be used as a clock which measures the time elapsed since it.
+#[cfg(feature = "std")]
+impl Clock for Instant {
+    fn now(&self) -> Duration {
+        self.elapsed()
+    }
+}
+
+/// Assembles a pulse sequence from the edges on a receiver's data pin, measuring pulse lengths
+/// with the given clock.
+///
+/// This doesn't depend on how edges are detected, so can be driven from an interrupt handler or a
+/// polling loop. Call [`edge`](Self::edge) for each edge, and [`timeout`](Self::timeout) if no edge
+/// arrives within the maximum pulse length while [`is_capturing`](Self::is_capturing).
+#[derive(Clone, Debug)]
+pub struct EdgeCapture<C> {
+    clock: C,
+    framer: Framer,
+    last_edge: Option<Duration>,
+}
+
+impl<C: Clock> EdgeCapture<C> {
+    /// Creates a new capture which starts with the first break pulse longer than
+    /// [`DEFAULT_BREAK_PULSE_LENGTH`].
+    pub fn new(clock: C) -> Self {
+        Self::with_break_pulse_length(
+            clock,
+            Duration::from_micros(DEFAULT_BREAK_PULSE_LENGTH.into()),
+        )
+    }
+
+    /// Creates a new capture which starts with the first break pulse longer than
+    /// `break_pulse_length`, and ends at the next one.
+    pub fn with_break_pulse_length(clock: C, break_pulse_length: Duration) -> Self {
+        Self {
+            clock,
+            framer: Framer::new(break_pulse_length),
+            last_edge: None,
+        }
+    }
+
+    /// Handles an edge which has just happened, where `high` is the level of the pin after it.
+    ///
+    /// Returns the sequence of pulse durations in microseconds, starting with the break pulse
+    /// before it, if this edge completed it.
+    pub fn edge(&mut self, high: bool) -> Option<Vec<u16>> {
+        let now = self.clock.now();
+        let last_edge = self.last_edge.replace(now)?;
+        // The pulse which just ended had the opposite level to the pin now.
+        if self.framer.pulse(now.saturating_sub(last_edge), !high) {
+            Some(self.framer.take())
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether a sequence has started, so the caller should call [`timeout`](Self::timeout)
+    /// if no edge arrives within the maximum pulse length.
+    pub fn is_capturing(&self) -> bool {
+        !self.framer.pulses.is_empty()
+    }
+
+    /// Ends the current sequence because no edge has arrived for too long, returning it if one had
+    /// started.
+    pub fn timeout(&mut self) -> Option<Vec<u16>> {
+        if self.is_capturing() {
+            Some(self.framer.take())
+        } else {
+            None
+        }
+    }
+}
+
+/// Splits a stream of pulses measured from a pin into a single code sequence, starting with the
+/// first break pulse.
+#[derive(Clone, Debug)]
+struct Framer {
+    break_pulse_length: Duration,
+    pulses: Vec<u16>,
+    last_pulse: Duration,
+}
+
+impl Framer {
+    fn new(break_pulse_length: Duration) -> Self {
+        Self::with_buffer(break_pulse_length, Vec::new())
+    }
+
+    /// Creates a new framer which stores pulses in the given empty buffer.
+    fn with_buffer(break_pulse_length: Duration, pulses: Vec<u16>) -> Self {
+        Self {
+            break_pulse_length,
+            pulses,
+            last_pulse: Duration::ZERO,
+        }
+    }
+
+    /// Handles a pulse which has just ended, where `high` is the level of the pin during the pulse.
+    ///
+    /// Returns true if the pulse completed the sequence.
+    fn pulse(&mut self, length: Duration, high: bool) -> bool {
+        if self.pulses.is_empty() {
+            // Wait for a long low pulse followed by a short high pulse to start.
+            if high && self.last_pulse > self.break_pulse_length && length < self.break_pulse_length
+            {
+                self.pulses.push(micros(self.last_pulse));
+                self.pulses.push(micros(length));
+            }
+            self.last_pulse = length;
+            false
+        } else {
+            self.pulses.push(micros(length));
+            self.last_pulse = length;
+            length > self.break_pulse_length
+        }
+    }
+
+    /// Returns the pulses of the sequence so far, and resets to wait for the next one.
+    ///
+    /// If the sequence ended with a break then it may also start the next sequence.
+    fn take(&mut self) -> Vec<u16> {
+        take(&mut self.pulses)
+    }
+}
+
+/// Converts the given duration to microseconds, saturating at `u16::MAX`.
+fn micros(duration: Duration) -> u16 {
+    duration.as_micros().try_into().unwrap_or(u16::MAX)
+}
+
+#[cfg(feature = "async")]
+mod asynch {
+    use super::{Clock, Framer, DEFAULT_MAX_PULSE_LENGTH};
+    use crate::DEFAULT_BREAK_PULSE_LENGTH;
+    use alloc::vec::Vec;
+    use core::{
+        future::{poll_fn, Future},
+        mem::take,
+        pin::pin,
+        task::Poll,
+        time::Duration,
+    };
+    use embedded_hal::digital::InputPin;
+    use embedded_hal_async::{delay::DelayNs, digital::Wait};
+
+    /// Receives pulse sequences from a pin by awaiting edge interrupts.
+    ///
+    /// # Example
+    ///
+    /// With embassy, such as on an nRF or ESP32 chip, where `rx_pin` is an input pin from the HAL
+    /// like `embassy_nrf::gpio::Input`:
+    ///
+    /// ```no_run
+    /// use core::time::Duration;
+    /// use embassy_time::{Delay, Instant};
+    /// use embedded_hal::digital::InputPin;
+    /// use embedded_hal_async::digital::Wait;
+    /// use rfbutton::{AsyncPulseReceiver, Clock};
+    ///
+    /// /// Measures the time elapsed since an instant with embassy's time driver.
+    /// struct EmbassyClock(Instant);
+    ///
+    /// impl Clock for EmbassyClock {
+    ///     fn now(&self) -> Duration {
+    ///         Duration::from_micros(self.0.elapsed().as_micros())
+    ///     }
+    /// }
+    ///
+    /// async fn receive_codes<P: InputPin + Wait>(rx_pin: P) -> Result<(), P::Error> {
+    ///     let mut receiver = AsyncPulseReceiver::new(rx_pin, EmbassyClock(Instant::now()), Delay);
+    ///     loop {
+    ///         let pulses = receiver.receive().await?;
+    ///         if let Ok(code) = rfbutton::decode(&pulses) {
+    ///             log::info!("Decoded: {:?}", code);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[derive(Debug)]
+    pub struct AsyncPulseReceiver<P, C, D> {
+        pin: P,
+        clock: C,
+        delay: D,
+        /// The minimum length of a pulse for it to be treated as a break between codes.
+        pub break_pulse_length: Duration,
+        /// The maximum time to wait for an edge once a code has started.
+        pub max_pulse_length: Duration,
+    }
+
+    impl<P: InputPin + Wait, C: Clock, D: DelayNs> AsyncPulseReceiver<P, C, D> {
+        /// Creates a new receiver for the given pin, using the given clock to measure pulse lengths
+        /// and the given delay for timeouts.
+        pub fn new(pin: P, clock: C, delay: D) -> Self {
+            Self {
+                pin,
+                clock,
+                delay,
+                break_pulse_length: Duration::from_micros(DEFAULT_BREAK_PULSE_LENGTH.into()),
+                max_pulse_length: DEFAULT_MAX_PULSE_LENGTH,
+            }
+        }
+
+        /// Waits for a single code, returning the sequence of pulse durations in microseconds
+        /// starting with the break pulse before it.
+        pub async fn receive(&mut self) -> Result<Vec<u16>, P::Error> {
+            let mut pulses = Vec::new();
+            self.receive_into(&mut pulses).await?;
+            Ok(pulses)
+        }
+
+        /// Waits for a single code, and replaces the contents of `pulses` with the sequence of
+        /// pulse durations in microseconds starting with the break pulse before it.
+        ///
+        /// This reuses the existing allocation of `pulses`, so passing the same buffer each time
+        /// avoids allocating for every code received. If there is an error then `pulses` is left
+        /// with whatever was received before it.
+        pub async fn receive_into(&mut self, pulses: &mut Vec<u16>) -> Result<(), P::Error> {
+            pulses.clear();
+            let mut framer = Framer::with_buffer(self.break_pulse_length, take(pulses));
+            let result = self.receive_frame(&mut framer).await;
+            *pulses = framer.pulses;
+            result
+        }
+
+        /// Feeds edges from the pin to the given framer until it completes a code or times out.
+        async fn receive_frame(&mut self, framer: &mut Framer) -> Result<(), P::Error> {
+            let mut last_timestamp = self.clock.now();
+
+            // Wait for as long as it takes for the start of a code.
+            while framer.pulses.is_empty() {
+                self.pin.wait_for_any_edge().await?;
+                let timestamp = self.clock.now();
+                let high = self.pin.is_low()?;
+                framer.pulse(timestamp - last_timestamp, high);
+                last_timestamp = timestamp;
+            }
+
+            // Read pulses until a break or a timeout.
+            while wait_for_edge_or_timeout(&mut self.pin, &mut self.delay, self.max_pulse_length)
+                .await?
+            {
+                let timestamp = self.clock.now();
+                let high = self.pin.is_low()?;
+                if framer.pulse(timestamp - last_timestamp, high) {
+                    break;
+                }
+                last_timestamp = timestamp;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Waits for an edge on the pin or for the timeout to elapse, whichever comes first.
+    ///
+    /// Returns true if there was an edge, or false on timeout.
+    async fn wait_for_edge_or_timeout<P: Wait, D: DelayNs>(
+        pin: &mut P,
+        delay: &mut D,
+        timeout: Duration,
+    ) -> Result<bool, P::Error> {
+        let mut edge = pin!(pin.wait_for_any_edge());
+        let mut timeout = pin!(delay.delay_us(timeout.as_micros().try_into().unwrap_or(u32::MAX)));
+        poll_fn(|cx| {
+            if let Poll::Ready(result) = edge.as_mut().poll(cx) {
+                Poll::Ready(result.map(|()| true))
+            } else if timeout.as_mut().poll(cx).is_ready() {
+                Poll::Ready(Ok(false))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(feature = "async")]
+pub use asynch::AsyncPulseReceiver;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::Cell, rc::Rc};
+
+    #[derive(Clone, Default)]
+    struct FakeClock(Rc<Cell<Duration>>);
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Duration {
+            self.0.get()
+        }
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn us(micros: u64) -> Duration {
+        Duration::from_micros(micros)
+    }
+
+    #[test]
+    fn framer_waits_for_break() {
+        let mut framer = Framer::new(ms(3));
+        assert!(!framer.pulse(us(300), true));
+        assert!(!framer.pulse(us(1000), false));
+        assert!(!framer.pulse(us(300), true));
+        assert!(!framer.pulse(ms(10), false));
+        assert!(framer.pulses.is_empty());
+        assert!(!framer.pulse(us(1000), true));
+        assert!(!framer.pulse(us(333), false));
+        assert!(framer.pulse(ms(5), true));
+        assert_eq!(framer.pulses, vec![10000, 1000, 333, 5000]);
+    }
+
+    /// Feeds the given edges, as the level after each edge and its timestamp in microseconds, to a
+    /// capture, and returns the sequences it completes.
+    fn capture(edges: &[(bool, u64)]) -> Vec<Vec<u16>> {
+        let clock = FakeClock::default();
+        let mut capture = EdgeCapture::new(clock.clone());
+        edges
+            .iter()
+            .filter_map(|&(high, timestamp)| {
+                clock.0.set(us(timestamp));
+                capture.edge(high)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn edge_capture() {
+        // No break.
+        assert_eq!(
+            capture(&[(true, 0), (false, 300), (true, 1300)]),
+            Vec::<Vec<u16>>::new()
+        );
+        // A break, a code, and a final break.
+        assert_eq!(
+            capture(&[
+                (false, 0),
+                (true, 10000),
+                (false, 11000),
+                (true, 11333),
+                (false, 16333),
+            ]),
+            vec![vec![10000, 1000, 333, 5000]]
+        );
+        // Two codes, where the break ending the first also starts the second.
+        assert_eq!(
+            capture(&[
+                (true, 0),
+                (false, 300),
+                (true, 10300),
+                (false, 11300),
+                (true, 11633),
+                (false, 11933),
+                (true, 21933),
+                (false, 22233),
+                (true, 23233),
+                (false, 23533),
+                (true, 33533),
+            ]),
+            vec![
+                vec![10000, 1000, 333, 300, 10000],
+                vec![10000, 300, 1000, 300, 10000]
+            ]
+        );
+    }
+
+    #[test]
+    fn edge_capture_timeout() {
+        let clock = FakeClock::default();
+        let mut capture = EdgeCapture::new(clock.clone());
+        assert_eq!(capture.timeout(), None);
+        for (high, timestamp) in [(false, 0), (true, 10000), (false, 11000), (true, 11333)] {
+            clock.0.set(us(timestamp));
+            assert_eq!(capture.edge(high), None);
+        }
+        assert!(capture.is_capturing());
+        assert_eq!(capture.timeout(), Some(vec![10000, 1000, 333]));
+        assert!(!capture.is_capturing());
+    }
+
+    #[cfg(feature = "async")]
+    mod asynch {
+        use super::super::*;
+        use super::FakeClock;
+        use core::{
+            convert::Infallible,
+            future::Future,
+            pin::pin,
+            task::{Context, Poll, Waker},
+        };
+        use embedded_hal::digital::{ErrorType, InputPin};
+        use embedded_hal_async::{delay::DelayNs, digital::Wait};
+        use std::collections::VecDeque;
+
+        /// A pin which plays back a scripted sequence of level changes.
+        struct FakePin {
+            clock: FakeClock,
+            level: bool,
+            edges: VecDeque<Duration>,
+        }
+
+        impl ErrorType for FakePin {
+            type Error = Infallible;
+        }
+
+        impl InputPin for FakePin {
+            fn is_high(&mut self) -> Result<bool, Infallible> {
+                Ok(self.level)
+            }
+
+            fn is_low(&mut self) -> Result<bool, Infallible> {
+                Ok(!self.level)
+            }
+        }
+
+        impl Wait for FakePin {
+            async fn wait_for_high(&mut self) -> Result<(), Infallible> {
+                if !self.level {
+                    self.wait_for_rising_edge().await?;
+                }
+                Ok(())
+            }
+
+            async fn wait_for_low(&mut self) -> Result<(), Infallible> {
+                if self.level {
+                    self.wait_for_falling_edge().await?;
+                }
+                Ok(())
+            }
+
+            async fn wait_for_rising_edge(&mut self) -> Result<(), Infallible> {
+                loop {
+                    self.wait_for_any_edge().await?;
+                    if self.level {
+                        return Ok(());
+                    }
+                }
+            }
+
+            async fn wait_for_falling_edge(&mut self) -> Result<(), Infallible> {
+                loop {
+                    self.wait_for_any_edge().await?;
+                    if !self.level {
+                        return Ok(());
+                    }
+                }
+            }
+
+            async fn wait_for_any_edge(&mut self) -> Result<(), Infallible> {
+                match self.edges.pop_front() {
+                    Some(pulse) => {
+                        self.clock.0.set(self.clock.0.get() + pulse);
+                        self.level = !self.level;
+                        Ok(())
+                    }
+                    None => core::future::pending().await,
+                }
+            }
+        }
+
+        struct ImmediateDelay;
+
+        impl DelayNs for ImmediateDelay {
+            async fn delay_ns(&mut self, _ns: u32) {}
+        }
+
+        fn block_on<F: Future>(future: F) -> F::Output {
+            let mut future = pin!(future);
+            let mut context = Context::from_waker(Waker::noop());
+            loop {
+                if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                    return output;
+                }
+            }
+        }
+
+        #[test]
+        fn fake_pin_waits_for_level() {
+            let clock = FakeClock::default();
+            let mut pin = FakePin {
+                clock: clock.clone(),
+                level: true,
+                edges: [300, 1000, 333]
+                    .into_iter()
+                    .map(Duration::from_micros)
+                    .collect(),
+            };
+            assert_eq!(block_on(pin.wait_for_high()), Ok(()));
+            assert_eq!(clock.now(), Duration::ZERO);
+            assert_eq!(block_on(pin.wait_for_rising_edge()), Ok(()));
+            assert_eq!(clock.now(), Duration::from_micros(1300));
+            assert_eq!(block_on(pin.wait_for_low()), Ok(()));
+            assert_eq!(clock.now(), Duration::from_micros(1633));
+            assert_eq!(pin.is_low(), Ok(true));
+        }
+
+        #[test]
+        fn receive_until_timeout() {
+            let clock = FakeClock::default();
+            let pin = FakePin {
+                clock: clock.clone(),
+                level: true,
+                edges: [300, 10000, 1000, 333, 333, 1000]
+                    .into_iter()
+                    .map(Duration::from_micros)
+                    .collect(),
+            };
+            let mut receiver = AsyncPulseReceiver::new(pin, clock, ImmediateDelay);
+            assert_eq!(
+                block_on(receiver.receive()),
+                Ok(vec![10000, 1000, 333, 333, 1000])
+            );
+        }
+
+        #[test]
+        fn receive_into_buffer() {
+            let clock = FakeClock::default();
+            let pin = FakePin {
+                clock: clock.clone(),
+                level: true,
+                edges: [300, 10000, 1000, 333, 333, 1000, 10000, 10000, 333, 1000]
+                    .into_iter()
+                    .map(Duration::from_micros)
+                    .collect(),
+            };
+            let mut receiver = AsyncPulseReceiver::new(pin, clock, ImmediateDelay);
+            let mut pulses = Vec::with_capacity(100);
+            pulses.push(42);
+            let buffer = pulses.as_ptr();
+            assert_eq!(block_on(receiver.receive_into(&mut pulses)), Ok(()));
+            assert_eq!(pulses, vec![10000, 1000, 333, 333, 1000, 10000]);
+            assert_eq!(block_on(receiver.receive_into(&mut pulses)), Ok(()));
+            assert_eq!(pulses, vec![10000, 333, 1000]);
+            assert_eq!(pulses.as_ptr(), buffer);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Compatibility with the `B1` messages used by the Portisch firmware of the Sonoff RF Bridge,
+//! which Home Assistant sends and receives via Tasmota's `RfRaw` command.
+
+use crate::{decode_with, Code, DecodeOptions, Polarity, DEFAULT_SHORT_DURATION, SYNC_MULTIPLE};
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+/// The bit set in a pulse's hex digit if it is a high pulse.
+const HIGH: u8 = 0x8;
+
+/// The index of the short pulse bucket in messages from [`Code::to_ha_string`].
+const SHORT: u8 = 0;
+/// The index of the long pulse bucket in messages from [`Code::to_ha_string`].
+const LONG: u8 = 1;
+/// The index of the sync break bucket in messages from [`Code::to_ha_string`].
+const SYNC: u8 = 2;
+
+impl Code {
+    /// Formats the code as a `B1` message for an RF Bridge running the Portisch firmware, with the
+    /// default short pulse duration of [`DEFAULT_SHORT_DURATION`] microseconds.
+    ///
+    /// A message is a string of hex digits like `"AA B1 03 0140 03C0 26C0 290909 ... 8 55"`, made
+    /// up of:
+    ///
+    /// - `AA` to start the message.
+    /// - `B1` for a message describing individual pulses.
+    /// - The number of distinct pulse durations, or buckets, as 2 hex digits.
+    /// - The duration of each bucket in microseconds, as 4 hex digits each.
+    /// - The pulses of the frame, one per hex digit. The low 3 bits of each digit are the index of
+    ///   its bucket, and the high bit is set for a high pulse and clear for a low pulse.
+    /// - `55` to end the message.
+    ///
+    /// The pulses are those of [`to_pulses`](Self::to_pulses) without the first and last, so the
+    /// frame starts with the low sync break and ends with the short high pulse before the next
+    /// break. The buckets are `[short, long, sync]`, so an `n` bit code has `n + 1` bytes of
+    /// pulses: `2` for the sync break, then the two digits `81` for each 0 bit or `90` for each 1
+    /// bit, then `8` for the final short pulse. The fields are separated by spaces as Tasmota does.
+    pub fn to_ha_string(&self) -> String {
+        let buckets = [
+            DEFAULT_SHORT_DURATION,
+            DEFAULT_SHORT_DURATION.saturating_mul(3),
+            DEFAULT_SHORT_DURATION.saturating_mul(SYNC_MULTIPLE),
+        ];
+        let mut pulses = Vec::with_capacity(2 * usize::from(self.length) + 2);
+        pulses.push(SYNC);
+        for bit in self.bits() {
+            if bit {
+                pulses.extend([HIGH | LONG, SHORT]);
+            } else {
+                pulses.extend([HIGH | SHORT, LONG]);
+            }
+        }
+        pulses.push(HIGH | SHORT);
+
+        let mut message = String::from("AA B1");
+        // Writing to a string can't fail.
+        let _ = write!(message, " {:02X}", buckets.len());
+        for bucket in buckets {
+            let _ = write!(message, " {:04X}", bucket);
+        }
+        message.push(' ');
+        for pulse in pulses {
+            let _ = write!(message, "{:X}", pulse);
+        }
+        message.push_str(" 55");
+        message
+    }
+
+    /// Parses a `B1` message sent to or received from an RF Bridge running the Portisch firmware,
+    /// and decodes the code from its pulses with the default [`DecodeOptions`].
+    ///
+    /// The message may have any buckets, and start with either a high or a low pulse. Spaces
+    /// between the fields are optional.
+    ///
+    /// Returns `None` if the message is not a valid `B1` message, or its pulses can't be decoded.
+    pub fn from_ha_string(s: &str) -> Option<Code> {
+        let s = s.replace(' ', "");
+        if !s.is_ascii() {
+            return None;
+        }
+        let fields = s.strip_prefix("AAB1")?.strip_suffix("55")?;
+        let bucket_count = usize::from(u8::from_str_radix(fields.get(..2)?, 16).ok()?);
+        let data = fields.get(2 + 4 * bucket_count..)?;
+        let buckets = (0..bucket_count)
+            .map(|i| u16::from_str_radix(&fields[2 + 4 * i..6 + 4 * i], 16).ok())
+            .collect::<Option<Vec<_>>>()?;
+        if data.len() % 2 != 0 {
+            return None;
+        }
+
+        let digits = data
+            .chars()
+            .map(|digit| digit.to_digit(16).map(|digit| digit as u8))
+            .collect::<Option<Vec<_>>>()?;
+        let first_high = digits.first()? & HIGH != 0;
+        let mut pulses = Vec::with_capacity(digits.len());
+        for (i, digit) in digits.into_iter().enumerate() {
+            // High and low pulses must alternate.
+            if (digit & HIGH != 0) != (first_high == (i % 2 == 0)) {
+                return None;
+            }
+            pulses.push(*buckets.get(usize::from(digit & !HIGH))?);
+        }
+
+        let options = DecodeOptions {
+            polarity: if first_high {
+                Polarity::Normal
+            } else {
+                Polarity::Inverted
+            },
+            ..Default::default()
+        };
+        decode_with(&pulses, &options).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let code = Code {
+            value: 0xe5cfd1,
+            length: 24,
+        };
+        let message = code.to_ha_string();
+        assert_eq!(
+            message,
+            "AA B1 03 0140 03C0 26C0 29090908181908190909081819090909090908190818181908 55"
+        );
+        assert_eq!(Code::from_ha_string(&message), Some(code));
+    }
+
+    #[test]
+    fn short_code() {
+        let code = Code {
+            value: 0b10,
+            length: 2,
+        };
+        assert_eq!(code.to_ha_string(), "AA B1 03 0140 03C0 26C0 290818 55");
+        assert_eq!(
+            Code::from_ha_string("AAB1030140 03C026C029081855"),
+            Some(code)
+        );
+    }
+
+    #[test]
+    fn parse_received() {
+        // A capture with the buckets in a different order, a high pulse first, and a trailing
+        // sync break.
+        assert_eq!(
+            Code::from_ha_string("AA B1 04 0138 03B7 2706 01AD 8290 8181 9082 55"),
+            Some(Code {
+                value: 0b1001,
+                length: 4
+            })
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            Code::from_ha_string("AA B0 03 0140 03C0 26C0 290818 55"),
+            None
+        );
+        assert_eq!(Code::from_ha_string("AA B1 03 0140 03C0 26C0 290818"), None);
+        assert_eq!(
+            Code::from_ha_string("AA B1 03 0140 03C0 26C0 29081 55"),
+            None
+        );
+        assert_eq!(
+            Code::from_ha_string("AA B1 03 0140 03C0 26C0 290838 55"),
+            None
+        );
+        assert_eq!(
+            Code::from_ha_string("AA B1 03 0140 03C0 26C0 299818 55"),
+            None
+        );
+        assert_eq!(
+            Code::from_ha_string("AA B1 03 0140 03C0 26C0 29G818 55"),
+            None
+        );
+        assert_eq!(
+            Code::from_ha_string("AA B1 03 0140 03C0 26C0 2908Ð8 55"),
+            None
+        );
+        assert_eq!(Code::from_ha_string("AA B1 03 0140 03C0 55"), None);
+        assert_eq!(Code::from_ha_string("AA B1 05 0140 55"), None);
+    }
+}
@@ -0,0 +1,97 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Conversion of sampled OOK waveforms, such as demodulated SDR output, to pulse durations.
+
+use alloc::vec::Vec;
+
+/// Runs of samples shorter than this many microseconds are treated as noise, and merged with the
+/// runs either side of them.
+const MIN_RUN_MICROS: u64 = 50;
+
+/// Converts a demodulated OOK waveform to a sequence of pulse durations in microseconds, starting
+/// with a high pulse, suitable for passing to [`decode`](crate::decode).
+///
+/// Samples greater than `threshold` are treated as high and others as low. Leading low samples are
+/// skipped, and runs shorter than 50 μs are merged with the runs either side of them to debounce
+/// noise around the threshold.
+///
+/// # Panics
+///
+/// Panics if `sample_rate` is 0.
+pub fn pulses_from_samples(samples: &[f32], sample_rate: u32, threshold: f32) -> Vec<u16> {
+    let Some(first_high) = samples.iter().position(|&sample| sample > threshold) else {
+        return Vec::new();
+    };
+
+    // Find the length in samples of each run of samples on the same side of the threshold.
+    let mut runs: Vec<u64> = Vec::new();
+    let mut level = true;
+    for &sample in &samples[first_high..] {
+        let high = sample > threshold;
+        match runs.last_mut() {
+            Some(run) if high == level => *run += 1,
+            _ => {
+                runs.push(1);
+                level = high;
+            }
+        }
+    }
+
+    let to_micros = |run: u64| run * 1_000_000 / u64::from(sample_rate);
+    let mut pulses: Vec<u64> = Vec::with_capacity(runs.len());
+    let mut runs = runs.into_iter();
+    while let Some(run) = runs.next() {
+        match pulses.last_mut() {
+            Some(last) if to_micros(run) < MIN_RUN_MICROS => {
+                // The run after the short one has the same level as the one before it.
+                *last += run + runs.next().unwrap_or(0);
+            }
+            _ => pulses.push(run),
+        }
+    }
+    pulses
+        .into_iter()
+        .map(|run| to_micros(run).try_into().unwrap_or(u16::MAX))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns `micros` microseconds of samples at 100 kHz with the given level.
+    fn level(high: bool, micros: usize) -> impl Iterator<Item = f32> {
+        std::iter::repeat_n(if high { 0.8 } else { 0.1 }, micros / 10)
+    }
+
+    #[test]
+    fn threshold_and_measure() {
+        let samples: Vec<f32> = level(false, 200)
+            .chain(level(true, 300))
+            .chain(level(false, 900))
+            .chain(level(true, 900))
+            .chain(level(false, 300))
+            .collect();
+        assert_eq!(
+            pulses_from_samples(&samples, 100_000, 0.5),
+            vec![300, 900, 900, 300]
+        );
+        assert_eq!(pulses_from_samples(&samples, 100_000, 0.9), vec![]);
+    }
+
+    #[test]
+    fn debounce() {
+        let samples: Vec<f32> = level(true, 300)
+            .chain(level(false, 400))
+            .chain(level(true, 20))
+            .chain(level(false, 480))
+            .chain(level(true, 900))
+            .collect();
+        assert_eq!(
+            pulses_from_samples(&samples, 100_000, 0.5),
+            vec![300, 900, 900]
+        );
+    }
+}
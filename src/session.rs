@@ -0,0 +1,70 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+use crate::Code;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// A record of the codes received during a capture session, for saving to disk.
+///
+/// Codes are serialized as hex strings in the same way as [`Code`] on its own, so for example a
+/// session can be written in TOML as:
+///
+/// ```toml
+/// frequency = 433920000
+/// timestamp = 1700000000
+/// codes = ["48b2a4", "48b2a8"]
+/// ```
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CaptureSession {
+    /// The frequency which was received on, in Hz.
+    pub frequency: u32,
+    /// The time at which the session started, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The codes which were received, in order.
+    pub codes: Vec<Code>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_test::{assert_tokens, Configure, Token};
+
+    #[test]
+    fn serde_session() {
+        assert_tokens(
+            &CaptureSession {
+                frequency: 433_920_000,
+                timestamp: 1_700_000_000,
+                codes: vec![
+                    Code {
+                        value: 0x48b2a4,
+                        length: 24,
+                    },
+                    Code {
+                        value: 0x0a,
+                        length: 8,
+                    },
+                ],
+            }
+            .readable(),
+            &[
+                Token::Struct {
+                    name: "CaptureSession",
+                    len: 3,
+                },
+                Token::Str("frequency"),
+                Token::U32(433_920_000),
+                Token::Str("timestamp"),
+                Token::U64(1_700_000_000),
+                Token::Str("codes"),
+                Token::Seq { len: Some(2) },
+                Token::Str("48b2a4"),
+                Token::Str("0a"),
+                Token::SeqEnd,
+                Token::StructEnd,
+            ],
+        );
+    }
+}
@@ -0,0 +1,21 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Bindings for using the decoder from JavaScript via `wasm-bindgen`.
+//!
+//! [`Code`] is exported as a JavaScript class with `value` and `length` properties, where `value`
+//! is a `BigInt`.
+
+use crate::Code;
+use wasm_bindgen::prelude::*;
+
+/// Given a sequence of pulse durations in microseconds (starting with a high pulse), try to decode
+/// a button code, as for [`crate::decode`].
+///
+/// From JavaScript the pulses may be passed as a `Uint16Array`, and an error is thrown if decoding
+/// fails.
+#[wasm_bindgen(js_name = decode)]
+pub fn decode(pulses: &[u16]) -> Result<Code, JsError> {
+    crate::decode(pulses).map_err(JsError::from)
+}
@@ -0,0 +1,99 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! An alternative serialization format for [`Code`] which includes the length explicitly, so that
+//! codes of any length round-trip.
+//!
+//! Codes are serialized as strings like `"19bit:0x4763a"`. Use it with
+//! `#[serde(with = "rfbutton::with_length")]`. The default serialization of [`Code`] also uses this
+//! format for codes whose length isn't a multiple of 4, and for empty codes.
+
+use crate::Code;
+use alloc::{format, string::String};
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+/// Serializes the given code as a string including its length, like `"19bit:0x4763a"`.
+pub fn serialize<S: Serializer>(code: &Code, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{}bit:{:#x}", code.length, code.value))
+}
+
+/// Deserializes a code from a string including its length, like `"19bit:0x4763a"`.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Code, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    parse(&s).ok_or_else(|| {
+        de::Error::invalid_value(
+            de::Unexpected::Str(&s),
+            &"a code like \"19bit:0x4763a\" with a value which fits in the length",
+        )
+    })
+}
+
+/// Parses a code from a string like `"19bit:0x4763a"`, returning `None` if it is not valid.
+pub(crate) fn parse(s: &str) -> Option<Code> {
+    let (length, value) = s.split_once("bit:0x")?;
+    let length = length.parse::<u8>().ok()?;
+    let value = u128::from_str_radix(value, 16).ok()?;
+    Code::new(value, length).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Serialize, Serializer};
+    use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct WithLength(Code);
+
+    impl Serialize for WithLength {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serialize(&self.0, serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for WithLength {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserialize(deserializer).map(Self)
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        assert_tokens(
+            &WithLength(Code {
+                value: 0x4763a,
+                length: 19,
+            }),
+            &[Token::Str("19bit:0x4763a")],
+        );
+        assert_tokens(
+            &WithLength(Code {
+                value: 0,
+                length: 0,
+            }),
+            &[Token::Str("0bit:0x0")],
+        );
+        assert_tokens(
+            &WithLength(Code {
+                value: 0xffffffff,
+                length: 32,
+            }),
+            &[Token::Str("32bit:0xffffffff")],
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        for s in ["4763a", "17bit:4763a", "129bit:0x1", "3bit:0x8", "bit:0x1"] {
+            assert_de_tokens_error::<WithLength>(
+                &[Token::Str(s)],
+                &format!(
+                    "invalid value: string {:?}, expected a code like \"19bit:0x4763a\" with a \
+                     value which fits in the length",
+                    s
+                ),
+            );
+        }
+    }
+}
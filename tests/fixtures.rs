@@ -0,0 +1,62 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Regression tests against recorded captures.
+//!
+//! Each capture in `tests/fixtures` is a pair of files: `<name>.pulses` with the pulse durations in
+//! microseconds separated by whitespace, starting with a high pulse, and `<name>.expected` with the
+//! code which it should decode to in the form `24bit:0x48b2a4`. New fixtures can be added just by
+//! adding a new pair of files.
+
+use rfbutton::{decode, Code};
+use std::{fs, path::Path};
+
+/// Parses a code in the form `24bit:0x48b2a4`.
+fn parse_code(s: &str) -> Code {
+    let (length, value) = s
+        .trim()
+        .split_once("bit:0x")
+        .unwrap_or_else(|| panic!("Invalid code {:?}", s));
+    Code::new(
+        u128::from_str_radix(value, 16).unwrap(),
+        length.parse().unwrap(),
+    )
+    .unwrap()
+}
+
+/// Parses pulse durations separated by whitespace.
+fn parse_pulses(s: &str) -> Vec<u16> {
+    s.split_whitespace()
+        .map(|pulse| pulse.parse().unwrap())
+        .collect()
+}
+
+#[test]
+fn decode_fixtures() {
+    let fixtures_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut count = 0;
+    for entry in fs::read_dir(&fixtures_dir).unwrap() {
+        let pulses_path = entry.unwrap().path();
+        if pulses_path
+            .extension()
+            .is_none_or(|extension| extension != "pulses")
+        {
+            continue;
+        }
+        let expected_path = pulses_path.with_extension("expected");
+        let pulses = parse_pulses(&fs::read_to_string(&pulses_path).unwrap());
+        let expected = parse_code(
+            &fs::read_to_string(&expected_path)
+                .unwrap_or_else(|e| panic!("Couldn't read {}: {}", expected_path.display(), e)),
+        );
+        assert_eq!(
+            decode(&pulses),
+            Ok(expected),
+            "Fixture {}",
+            pulses_path.display()
+        );
+        count += 1;
+    }
+    assert_ne!(count, 0, "No fixtures found");
+}
@@ -0,0 +1,114 @@
+// Copyright 2026 the rfbutton authors.
+// This project is dual-licensed under Apache 2.0 and MIT terms.
+// See LICENSE-APACHE and LICENSE-MIT for details.
+
+//! Checks that decoding doesn't allocate, so it can be used on targets without a heap.
+//!
+//! This counts allocations at run time under `std`. The `no_std_check` crate also checks that a
+//! `#![no_std]` binary which decodes codes links without `alloc`.
+
+use rfbutton::{decode, decode_all_into, decode_with, Code, DecodeOptions};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fs,
+    path::Path,
+};
+
+thread_local! {
+    /// The number of allocations made by the current thread while counting, or `None` if not
+    /// counting.
+    static ALLOCATIONS: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Counts allocations made by each thread, as other tests may be running at the same time.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // The thread local may already have been destroyed if the thread is exiting.
+        let _ = ALLOCATIONS.try_with(|allocations| {
+            if let Some(count) = allocations.get() {
+                allocations.set(Some(count + 1));
+            }
+        });
+        // SAFETY: The caller upholds the requirements of `GlobalAlloc::alloc`.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: The caller upholds the requirements of `GlobalAlloc::dealloc`.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the number of allocations made by the current thread while running `f`.
+fn count_allocations(f: impl FnOnce()) -> usize {
+    ALLOCATIONS.set(Some(0));
+    f();
+    ALLOCATIONS.replace(None).unwrap()
+}
+
+fn doorbell() -> Vec<u16> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/doorbell.pulses");
+    fs::read_to_string(path)
+        .unwrap()
+        .split_whitespace()
+        .map(|pulse| pulse.parse().unwrap())
+        .collect()
+}
+
+#[test]
+fn decode_without_allocating() {
+    let pulses = doorbell();
+    let options = DecodeOptions::default();
+    let expected = Code {
+        value: 0x48b2a4,
+        length: 24,
+    };
+    let mut codes = [Code {
+        value: 0,
+        length: 0,
+    }; 4];
+    let mut decoded = None;
+    let mut count = 0;
+
+    assert_eq!(
+        count_allocations(|| {
+            decoded = Some(decode(&pulses));
+            count = decode_all_into(&pulses, &options, &mut codes);
+        }),
+        0
+    );
+    assert_eq!(decoded, Some(Ok(expected)));
+    assert!(count > 1);
+    assert!(codes[..count].iter().all(|&code| code == expected));
+}
+
+#[test]
+fn guess_bits_without_allocating() {
+    // The third symbol isn't valid, so its bit is guessed.
+    let pulses = [
+        300, 10000, 900, 300, 300, 900, 600, 600, 900, 300, 300, 10000,
+    ];
+    let options = DecodeOptions {
+        resync_on_error: true,
+        ..Default::default()
+    };
+    let mut decoded = None;
+
+    assert_eq!(
+        count_allocations(|| decoded = Some(decode_with(&pulses, &options))),
+        0
+    );
+    assert_eq!(
+        decoded,
+        Some(Ok(Code {
+            value: 0b1001,
+            length: 4
+        }))
+    );
+}